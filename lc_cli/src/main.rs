@@ -1,49 +1,114 @@
-use lc_lib::{
-    common::*, edit::*, finish::*, hide::*, info::*, interpret::*, new::*, search::*, submit::*,
-    tag::*,
-};
+use lc_lib::{common::*, error::ErrorKind, git, repo::Repo, tag::*};
 
 use anyhow::Result;
 use clap::Parser;
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() {
     let args = Cli::parse();
+    if let Err(e) = run(args).await {
+        // the same report `main` returning the error would print, with an exit code per kind
+        eprintln!("Error: {e:?}");
+        std::process::exit(ErrorKind::of(&e).exit_code());
+    }
+}
+
+async fn run(args: Cli) -> Result<()> {
+    lc_lib::cache::set_mode(match (args.offline, args.refresh) {
+        (true, _) => lc_lib::cache::CacheMode::Offline,
+        (_, true) => lc_lib::cache::CacheMode::Refresh,
+        _ => lc_lib::cache::CacheMode::Normal,
+    });
+    let repo = &Repo::open()?;
     match &args.command {
         /**********************************************************************/
         // These are the commands that are most necessary
-        Commands::New { link } => lc_lib::new::run(link).await?,
+        Commands::New { problems, file } => {
+            let before = git::prepare(repo)?;
+            lc_lib::new::run_many(repo, problems.clone(), file.as_deref()).await?;
+            lc_lib::readme::hook(repo);
+            git::hook(repo, git::Action::New, before);
+        }
 
-        Commands::Edit { num } => lc_lib::edit::run(num)?,
+        Commands::Edit {
+            num,
+            watch,
+            no_editor,
+        } => lc_lib::edit::run(repo, num, *watch, *no_editor)?,
 
-        Commands::Tag { cmd } => tag_subcommands(cmd)?,
+        Commands::Tag { cmd } => match cmd {
+            TagCommand::Search { .. } | TagCommand::List => tag_subcommands(repo, cmd)?,
+            TagCommand::Add { .. }
+            | TagCommand::Remove { .. }
+            | TagCommand::Edit { .. }
+            | TagCommand::Migrate => {
+                let before = git::prepare(repo)?;
+                tag_subcommands(repo, cmd)?;
+                lc_lib::readme::hook(repo);
+                git::hook(repo, git::Action::Tag, before);
+            }
+        },
 
-        Commands::Test { num } => {
-            // same as submit but with the altered state of running the tests that LC provides
-        }
-        Commands::Submit { num } => {
-            // given a number should aim to send the code to LeetCode, but I have no idea on how
-            // to actually send this to them and receive the response. HTTP? GraphQL? I have no
-            // clue.
-        }
+        // runs the solution against the example cases that LC provides
+        Commands::Test { num } => lc_lib::interpret::run(repo, num).await?,
+
+        Commands::Submit { num } => lc_lib::submit::run(repo, num).await?,
         /**********************************************************************/
         // these are the commands that can be remade for other uses
-        Commands::Search { cmd } => {
+        Commands::Search { cmd: _ } => {
             // given any of (name, number, tag(s)) will find what you are searching for
         }
-        Commands::Info { num } => {
-            // takes a number and prints a bunch of info about the problem
+        Commands::Info { num } => lc_lib::info::run(repo, num)?,
+
+        Commands::Hide { num } => lc_lib::hide::run(repo, num)?,
+
+        // Maybe this can track multiple solutions as well to be able to compare them.
+        //
+        // Hopefully this can also have a way to see the time/space complexities and
+        // explanations of functions but we will see.
+        Commands::Finish { num } => {
+            let before = git::prepare(repo)?;
+            lc_lib::finish::run(repo, num)?;
+            lc_lib::readme::hook(repo);
+            git::hook(repo, git::Action::Finish, before);
         }
-        Commands::Hide { num } => {
-            // given a number will tag this as a hidden problem that has been attempted but not
-            // completed. This should maybe be pushed somewhere else or just not tracked.
+
+        Commands::Browse {
+            difficulty,
+            tag,
+            status,
+            keyword,
+            no_premium,
+            page,
+            per_page,
+            online,
+        } => {
+            let filter = lc_lib::browse::BrowseFilter {
+                difficulty: *difficulty,
+                tag: tag.clone(),
+                status: *status,
+                keyword: keyword.clone(),
+                exclude_premium: *no_premium,
+            };
+            lc_lib::browse::run(repo, filter, *page, *per_page, *online).await?
         }
-        Commands::Finish { num } => {
-            // Ceremoniously tags the problem as completed and with whichever solution was used.
-            // Maybe this can track multiple solutions as well to be able to compare them.
-            //
-            // Hopefully this can also have a way to see the time/space complexities and
-            // explanations of functions but we will see.
+
+        Commands::Catalog { cmd } => lc_lib::catalog::catalog_subcommands(repo, cmd).await?,
+
+        Commands::Import { cmd } => lc_lib::import::import_subcommands(repo, cmd).await?,
+
+        Commands::Daily { start } => lc_lib::daily::run(repo, *start).await?,
+
+        Commands::Review { num, start, grade } => lc_lib::review::run(repo, *num, *start, *grade)?,
+
+        Commands::Stats { json } => lc_lib::stats::run(repo, *json)?,
+
+        Commands::Log { num, limit } => git::run_log(repo, *num, *limit)?,
+
+        Commands::Readme => lc_lib::readme::run(repo)?,
+
+        Commands::Export { format, output } => {
+            lc_lib::export::run(repo, *format, output.as_deref())?
         }
         #[allow(unreachable_patterns)]
         a => println!("Input was: {a:?}"),
@@ -1,13 +1,11 @@
-use super::commands::new::sanitize_lc_link;
+use lc_lib::new::sanitize_lc_link;
 use regex::Regex;
 use reqwest::Url;
 use std::str::FromStr;
 
 #[test]
 fn parse_link_test_1() {
-    let out =
-        sanitize_lc_link(&"https://leetcode.com/problems/remove-duplicate-letters/".to_owned())
-            .unwrap();
+    let out = sanitize_lc_link("https://leetcode.com/problems/remove-duplicate-letters/").unwrap();
     println!("{}", out);
     assert_eq!(
         out,
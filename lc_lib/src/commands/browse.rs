@@ -0,0 +1,238 @@
+// paging through the problems on LeetCode to find new ones to track
+use anyhow::Result;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use std::io::IsTerminal;
+use strum::{Display, EnumString};
+
+use super::catalog::Catalog;
+use super::common::{prompt_for_input, tracked_problems, GQL_ENDPOINT, LEETCODE_HOST};
+use super::error::Error;
+use super::info::Difficulty;
+use super::new::{generate_request_client, query_endpoint};
+use super::repo::Repo;
+
+/// Where a problem is at on the LeetCode account, as opposed to in the repo.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display, EnumString)]
+#[strum(ascii_case_insensitive)]
+pub enum RemoteStatus {
+    Todo,
+    Solved,
+    Attempted,
+}
+
+impl RemoteStatus {
+    /// The value LeetCode's `status` filter expects.
+    fn filter_value(&self) -> &'static str {
+        match self {
+            RemoteStatus::Todo => "NOT_STARTED",
+            RemoteStatus::Solved => "AC",
+            RemoteStatus::Attempted => "TRIED",
+        }
+    }
+}
+
+/// Narrows down the problems shown by `lc browse`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BrowseFilter {
+    pub difficulty: Option<Difficulty>,
+    /// The slug of a topic tag, e.g. `dynamic-programming`
+    pub tag: Option<String>,
+    pub status: Option<RemoteStatus>,
+    pub keyword: Option<String>,
+    pub exclude_premium: bool,
+}
+
+impl BrowseFilter {
+    /// The `filters` variable of the `problemsetQuestionList` query.
+    pub fn to_json(&self) -> serde_json::Value {
+        let mut out = serde_json::json!({});
+        if let Some(a) = self.difficulty {
+            out["difficulty"] = a.to_string().to_uppercase().into();
+        }
+        if let Some(a) = &self.tag {
+            out["tags"] = serde_json::json!([a]);
+        }
+        if let Some(a) = self.status {
+            out["status"] = a.filter_value().into();
+        }
+        if let Some(a) = &self.keyword {
+            out["searchKeywords"] = a.as_str().into();
+        }
+        if self.exclude_premium {
+            out["premiumOnly"] = false.into();
+        }
+        out
+    }
+}
+
+/// A problem in LeetCode's problem list.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ListedProblem {
+    // the backend id used when submitting, can differ from the number shown on the site
+    pub question_id: usize,
+    pub frontend_id: usize,
+    pub title: String,
+    pub title_slug: String,
+    pub difficulty: Option<Difficulty>,
+    pub paid_only: bool,
+    /// Slugs of the topic tags
+    pub topics: Vec<String>,
+    #[serde(skip)]
+    pub status: Option<RemoteStatus>,
+}
+
+impl ListedProblem {
+    pub fn from_json(json: &serde_json::Value) -> Result<ListedProblem> {
+        let field = |key: &str| match json[key].as_str() {
+            Some(a) => Ok(a.to_owned()),
+            None => Err(Error::parse(format!(
+                "Could not get the {key} of a listed problem from JSON"
+            ))),
+        };
+        Ok(ListedProblem {
+            question_id: field("questionId")?.parse()?,
+            frontend_id: field("frontendQuestionId")?.parse()?,
+            title: field("title")?,
+            title_slug: field("titleSlug")?,
+            difficulty: field("difficulty")?.parse().ok(),
+            paid_only: json["paidOnly"].as_bool().unwrap_or(false),
+            topics: json["topicTags"]
+                .as_array()
+                .map(|tags| {
+                    tags.iter()
+                        .filter_map(|a| a["slug"].as_str().map(str::to_owned))
+                        .collect()
+                })
+                .unwrap_or_default(),
+            status: match json["status"].as_str() {
+                Some("ac") => Some(RemoteStatus::Solved),
+                Some("notac") => Some(RemoteStatus::Attempted),
+                _ => None,
+            },
+        })
+    }
+
+    pub fn link(&self) -> String {
+        format!("https://{LEETCODE_HOST}/problems/{}/", self.title_slug)
+    }
+}
+
+/// One page of the problem list, along with how many problems match the filter in total.
+pub async fn fetch_page(
+    client: &reqwest::Client,
+    filter: &BrowseFilter,
+    skip: usize,
+    limit: usize,
+) -> Result<(usize, Vec<ListedProblem>)> {
+    let query = serde_json::json!({"query":"\n    query problemsetQuestionList($categorySlug: String, $limit: Int, $skip: Int, $filters: QuestionListFilterInput) {\n  problemsetQuestionList: questionList(\n    categorySlug: $categorySlug\n    limit: $limit\n    skip: $skip\n    filters: $filters\n  ) {\n    total: totalNum\n    questions: data {\n      difficulty\n      questionId\n      frontendQuestionId: questionFrontendId\n      paidOnly: isPaidOnly\n      status\n      title\n      titleSlug\n      topicTags {\n        name\n        slug\n      }\n    }\n  }\n}\n    ","variables":{"categorySlug":"","skip":skip,"limit":limit,"filters":filter.to_json()},"operationName":"problemsetQuestionList"});
+    let data = query_endpoint(&GQL_ENDPOINT.to_string(), &query, client).await?;
+    let list = &data["data"]["problemsetQuestionList"];
+    let Some(questions) = list["questions"].as_array() else {
+        return Err(Error::parse("Could not get the problemsetQuestionList from JSON").into());
+    };
+    let mut out = questions
+        .iter()
+        .map(ListedProblem::from_json)
+        .collect::<Result<Vec<_>>>()?;
+    // in case the filter is ignored by LeetCode
+    if filter.exclude_premium {
+        out.retain(|a| !a.paid_only);
+    }
+    Ok((list["total"].as_u64().unwrap_or(0) as usize, out))
+}
+
+/// Prints a page of problems, marking the ones tracked in the repo with `*`.
+pub fn print_page(problems: &[ListedProblem], tracked: &[usize]) {
+    let width = problems.iter().map(|a| a.title.len()).max().unwrap_or(0);
+    for prob in problems {
+        let mark = if tracked.contains(&prob.frontend_id) {
+            '*'
+        } else {
+            ' '
+        };
+        let difficulty = prob
+            .difficulty
+            .map(|a| a.to_string())
+            .unwrap_or("-".to_owned());
+        let premium = if prob.paid_only { " (premium)" } else { "" };
+        println!(
+            "{mark} {:>5}  {:<width$}  {difficulty:<6}{premium}",
+            prob.frontend_id, prob.title
+        );
+    }
+}
+
+/// Where `lc browse` gets its problems from.
+enum Source {
+    Catalog(Catalog),
+    Remote(reqwest::Client),
+}
+
+/// Pages through the catalog when there is one, unless `online` is set or the filter needs the
+/// LeetCode account. `page` and `per_page` are at least one, as the CLI checks.
+pub async fn run(
+    repo: &Repo,
+    filter: BrowseFilter,
+    page: usize,
+    per_page: usize,
+    online: bool,
+) -> Result<()> {
+    let catalog = match online || filter.status.is_some() {
+        true => None,
+        false => Catalog::read(repo)?,
+    };
+    let source = match catalog {
+        Some(a) => Source::Catalog(a),
+        None => Source::Remote(generate_request_client(&Url::parse(&format!(
+            "https://{LEETCODE_HOST}/problemset/"
+        ))?)?),
+    };
+    let mut page = page;
+    loop {
+        let skip = (page - 1) * per_page;
+        let (total, problems) = match &source {
+            Source::Catalog(catalog) => {
+                let found = catalog.filter(&filter);
+                let problems = found.iter().skip(skip).take(per_page);
+                (found.len(), problems.map(|a| (*a).clone()).collect())
+            }
+            Source::Remote(client) => fetch_page(client, &filter, skip, per_page).await?,
+        };
+        let tracked = tracked_problems(repo)?;
+        let pages = total.div_ceil(per_page).max(1);
+        println!("Page {page} of {pages} ({total} problems, * is tracked)");
+        print_page(&problems, &tracked);
+
+        if !std::io::stdin().is_terminal() {
+            return Ok(());
+        }
+        let (input, _) = prompt_for_input::<String>(
+            "Numbers to start tracking, n/p for the next/previous page, or enter to quit: ",
+        )?;
+        match input.as_str() {
+            "" | "q" => return Ok(()),
+            "n" => page = (page + 1).min(pages),
+            "p" => page = page.saturating_sub(1).max(1),
+            _ => {
+                for num in input.split([' ', ',']).filter(|a| !a.is_empty()) {
+                    let found = num
+                        .parse::<usize>()
+                        .ok()
+                        .and_then(|num| problems.iter().find(|a| a.frontend_id == num));
+                    let Some(prob) = found else {
+                        println!("{num} is not on this page");
+                        continue;
+                    };
+                    if tracked.contains(&prob.frontend_id) {
+                        println!("Problem {} is already tracked", prob.frontend_id);
+                        continue;
+                    }
+                    if let Err(e) = super::new::run(repo, &prob.link()).await {
+                        println!("Could not start Problem {}: {e}", prob.frontend_id);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,170 @@
+// responses from LeetCode's GraphQL endpoint kept on disk so they can be reused offline
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use super::error::Error;
+use super::fs;
+
+/// How `query_endpoint` uses the cache.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CacheMode {
+    /// Answer from the cache while it is fresh, otherwise ask LeetCode and remember the answer
+    #[default]
+    Normal,
+    /// Never ask LeetCode, answering from the cache however old it is
+    Offline,
+    /// Always ask LeetCode, replacing what is in the cache
+    Refresh,
+}
+
+static MODE: AtomicU8 = AtomicU8::new(0);
+
+/// Sets the mode for every query made from now on, like `--offline` does.
+pub fn set_mode(mode: CacheMode) {
+    MODE.store(mode as u8, Ordering::Relaxed);
+}
+
+pub fn mode() -> CacheMode {
+    match MODE.load(Ordering::Relaxed) {
+        1 => CacheMode::Offline,
+        2 => CacheMode::Refresh,
+        _ => CacheMode::Normal,
+    }
+}
+
+/// Fails when offline, for requests that can't be answered from the cache.
+pub fn ensure_online(doing: &str) -> Result<()> {
+    match mode() {
+        CacheMode::Offline => Err(Error::network(format!(
+            "Can't be {doing} while offline, run without --offline"
+        ))
+        .into()),
+        _ => Ok(()),
+    }
+}
+
+/// How long the response to `operation` stays fresh, `None` for queries that aren't cached.
+pub fn ttl(operation: &str) -> Option<Duration> {
+    match operation {
+        // problem statements and code snippets hardly ever change
+        "questionEditorData" | "consolePanelConfig" | "questionTitle" => Some(Duration::days(30)),
+        // a submission never changes once judged
+        "submissionDetails" => Some(Duration::days(365)),
+        "questionOfToday" => Some(Duration::hours(1)),
+        // the problem list and submissions change with every solve, so they are only kept for
+        // --offline and always asked for again while online
+        "problemsetQuestionList" | "submissionList" => Some(Duration::zero()),
+        _ => None,
+    }
+}
+
+/// Whether the response to `operation` depends on who is logged in, like the solved status in
+/// the problem list, so each account gets its own entry.
+pub fn per_account(operation: &str) -> bool {
+    matches!(
+        operation,
+        "problemsetQuestionList" | "submissionList" | "submissionDetails"
+    )
+}
+
+/// FNV-1a, which unlike the std hashers is stable between Rust versions.
+fn hash(text: &str) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in text.bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+/// A GraphQL query as far as the cache is concerned: its operation and variables, and for
+/// queries answered per account a hash of the session that asked.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CacheKey {
+    pub operation: String,
+    pub variables: serde_json::Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub account: Option<String>,
+}
+
+impl CacheKey {
+    /// The key of `query` for whoever `$LEETCODE_SESSION` logs in as.
+    pub fn of(query: &serde_json::Value) -> CacheKey {
+        CacheKey::for_session(query, std::env::var("LEETCODE_SESSION").ok().as_deref())
+    }
+
+    pub fn for_session(query: &serde_json::Value, session: Option<&str>) -> CacheKey {
+        let operation = query["operationName"].as_str().unwrap_or("").to_owned();
+        // the session itself is a secret, so only its hash is written down
+        let account = session
+            .filter(|_| per_account(&operation))
+            .map(|a| format!("{:016x}", hash(a)));
+        CacheKey {
+            operation,
+            variables: query["variables"].clone(),
+            account,
+        }
+    }
+
+    /// The file name of the entry. Object keys serialize in sorted order, so the same variables
+    /// always give the same name.
+    pub fn file_name(&self) -> String {
+        let mut text = self.variables.to_string();
+        if let Some(a) = &self.account {
+            text.push_str(a);
+        }
+        format!("{}-{:016x}.json", self.operation, hash(&text))
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CacheEntry {
+    pub key: CacheKey,
+    pub fetched: DateTime<Utc>,
+    pub response: serde_json::Value,
+}
+
+impl CacheEntry {
+    pub fn is_fresh(&self, now: DateTime<Utc>) -> bool {
+        ttl(&self.key.operation).is_some_and(|ttl| now - self.fetched < ttl)
+    }
+}
+
+/// `$LEETCODE_CACHE_DIR`, falling back to `lc` in the user's cache directory.
+pub fn cache_dir() -> Result<PathBuf> {
+    if let Ok(a) = std::env::var("LEETCODE_CACHE_DIR") {
+        return Ok(PathBuf::from(a));
+    }
+    if let Ok(a) = std::env::var("XDG_CACHE_HOME") {
+        return Ok(PathBuf::from(a).join("lc"));
+    }
+    match std::env::var("HOME") {
+        Ok(a) => Ok(PathBuf::from(a).join(".cache").join("lc")),
+        Err(_) => {
+            Err(Error::config("Could not find a cache directory, set LEETCODE_CACHE_DIR").into())
+        }
+    }
+}
+
+/// The cached entry for `key` whether or not it is fresh.
+pub fn read(key: &CacheKey) -> Option<CacheEntry> {
+    let path = cache_dir().ok()?.join(key.file_name());
+    let entry: CacheEntry = serde_json::from_str(&std::fs::read_to_string(path).ok()?).ok()?;
+    // guards against two keys hashing to the same file
+    (entry.key == *key).then_some(entry)
+}
+
+pub fn write(key: &CacheKey, response: &serde_json::Value) -> Result<()> {
+    let dir = cache_dir()?;
+    fs::create_dir_all(&dir)?;
+    let entry = CacheEntry {
+        key: key.clone(),
+        fetched: Utc::now(),
+        response: response.clone(),
+    };
+    fs::replace(dir.join(key.file_name()), serde_json::to_string(&entry)?)?;
+    Ok(())
+}
@@ -0,0 +1,289 @@
+// a copy of LeetCode's problem list kept in the repo so it can be used offline
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use clap::Subcommand;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::hash::{BuildHasher, Hasher};
+
+use super::browse::{fetch_page, BrowseFilter, ListedProblem};
+use super::common::{tracked_problems, LEETCODE_HOST};
+use super::error::Error;
+use super::info::Difficulty;
+use super::new::generate_request_client;
+use super::repo::Repo;
+
+/// How many problems are asked for at once while syncing.
+const SYNC_PAGE: usize = 500;
+
+#[derive(Subcommand, Debug)]
+pub enum CatalogCommand {
+    /// Download the problem list, fetching past the first page only when something changed
+    ///
+    /// When the number of problems and the first page are the same as last time nothing else is
+    /// fetched, so an edit further down is only picked up by `--full` or the next sync that finds
+    /// a change
+    Sync {
+        /// Fetch every page into an empty catalog, whatever changed
+        #[arg(long)]
+        full: bool,
+    },
+    /// Show the slug and title of a problem number
+    Lookup { num: usize },
+    /// Pick a random problem that isn't tracked yet
+    Random {
+        #[arg(short, long)]
+        difficulty: Option<Difficulty>,
+        /// The slug of a topic tag, e.g. dynamic-programming
+        #[arg(short, long)]
+        tag: Option<String>,
+        /// Leave out premium problems
+        #[arg(long)]
+        no_premium: bool,
+        /// Start tracking the problem like `new` would
+        #[arg(long)]
+        start: bool,
+    },
+}
+
+/// Every public problem on LeetCode as of the last sync, stored as `CATALOG.json` at the top of
+/// the repo.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct Catalog {
+    pub synced: Option<DateTime<Utc>>,
+    pub problems: Vec<ListedProblem>,
+}
+
+impl Catalog {
+    /// Reads the catalog, `None` when it was never synced.
+    pub fn read(repo: &Repo) -> Result<Option<Catalog>> {
+        match repo.read_optional(&repo.catalog())? {
+            Some(a) => Ok(Some(serde_json::from_str(&a)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Reads the catalog, failing when it was never synced.
+    pub fn load(repo: &Repo) -> Result<Catalog> {
+        match Catalog::read(repo)? {
+            Some(a) => Ok(a),
+            None => {
+                Err(Error::config("There is no catalog yet, run `lc catalog sync` first").into())
+            }
+        }
+    }
+
+    /// Replaces the catalog file, going through a temporary file like TAGS does.
+    pub fn write(&self, repo: &Repo) -> Result<()> {
+        repo.replace(&repo.catalog(), &serde_json::to_string(self)?)?;
+        Ok(())
+    }
+
+    /// Adds or replaces problems, keeping the catalog sorted by problem number.
+    pub fn merge(&mut self, problems: Vec<ListedProblem>) {
+        for mut prob in problems {
+            // the status belongs to whoever synced, not to the catalog
+            prob.status = None;
+            match self
+                .problems
+                .iter_mut()
+                .find(|a| a.question_id == prob.question_id)
+            {
+                Some(a) => *a = prob,
+                None => self.problems.push(prob),
+            }
+        }
+        self.problems.sort_by_key(|a| a.frontend_id);
+    }
+
+    /// Merges a page of LeetCode's list that starts at `skip`, unless the catalog already has
+    /// exactly those problems there. Returns how many problems were added and how many changed.
+    pub fn update_page(&mut self, skip: usize, mut page: Vec<ListedProblem>) -> (usize, usize) {
+        page.iter_mut().for_each(|a| a.status = None);
+        if self.problems.get(skip..skip + page.len()) == Some(&page[..]) {
+            return (0, 0);
+        }
+        let (mut added, mut updated) = (0, 0);
+        for prob in &page {
+            match self
+                .problems
+                .iter()
+                .find(|a| a.question_id == prob.question_id)
+            {
+                Some(a) if a != prob => updated += 1,
+                Some(_) => {}
+                None => added += 1,
+            }
+        }
+        self.merge(page);
+        (added, updated)
+    }
+
+    /// Whether LeetCode still lists `total` problems and starts with `first`, as the catalog does.
+    pub fn unchanged(&self, total: usize, first: &[ListedProblem]) -> bool {
+        self.problems.len() == total
+            && self.problems.len() >= first.len()
+            && first.iter().zip(&self.problems).all(|(a, b)| {
+                // the status belongs to whoever synced, not to the catalog
+                ListedProblem {
+                    status: None,
+                    ..a.clone()
+                } == *b
+            })
+    }
+
+    pub fn by_number(&self, num: usize) -> Option<&ListedProblem> {
+        self.problems.iter().find(|a| a.frontend_id == num)
+    }
+
+    pub fn by_slug(&self, slug: &str) -> Option<&ListedProblem> {
+        self.problems.iter().find(|a| a.title_slug == slug)
+    }
+
+    /// The problems that match `filter`. The status filter needs the LeetCode account so it
+    /// is ignored here.
+    pub fn filter(&self, filter: &BrowseFilter) -> Vec<&ListedProblem> {
+        let keyword = filter.keyword.as_ref().map(|a| a.to_lowercase());
+        self.problems
+            .iter()
+            .filter(|a| filter.difficulty.is_none_or(|d| a.difficulty == Some(d)))
+            .filter(|a| filter.tag.as_ref().is_none_or(|t| a.topics.contains(t)))
+            .filter(|a| !(filter.exclude_premium && a.paid_only))
+            .filter(|a| {
+                keyword.as_ref().is_none_or(|k| {
+                    a.title.to_lowercase().contains(k)
+                        || a.title_slug.contains(k)
+                        || a.frontend_id.to_string() == *k
+                })
+            })
+            .collect()
+    }
+}
+
+/// What a sync changed in the catalog.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SyncChanges {
+    pub added: usize,
+    pub updated: usize,
+    pub removed: usize,
+}
+
+/// Brings the catalog up to date. The first page and the number of problems are checked first,
+/// when neither changed the catalog is taken as current and nothing more is fetched. Otherwise
+/// every page is fetched, only the ones that differ from the catalog are merged and problems
+/// LeetCode no longer lists are dropped. With `full` the old catalog isn't even read.
+pub async fn sync(repo: &Repo, full: bool) -> Result<(Catalog, SyncChanges)> {
+    let client = generate_request_client(&Url::parse(&format!(
+        "https://{LEETCODE_HOST}/problemset/"
+    ))?)?;
+    let filter = BrowseFilter::default();
+    let mut catalog = match full {
+        true => Catalog::default(),
+        false => Catalog::read(repo)?.unwrap_or_default(),
+    };
+    let mut changes = SyncChanges::default();
+    let mut listed = HashSet::new();
+    let (total, mut page) = fetch_page(&client, &filter, 0, SYNC_PAGE).await?;
+    if catalog.unchanged(total, &page) {
+        catalog.synced = Some(Utc::now());
+        catalog.write(repo)?;
+        return Ok((catalog, changes));
+    }
+    let mut skip = 0;
+    while !page.is_empty() {
+        listed.extend(page.iter().map(|a| a.question_id));
+        let len = page.len();
+        let (added, updated) = catalog.update_page(skip, page);
+        changes.added += added;
+        changes.updated += updated;
+        skip += len;
+        if skip >= total {
+            break;
+        }
+        page = fetch_page(&client, &filter, skip, SYNC_PAGE).await?.1;
+    }
+    // a list cut short can't tell which problems are gone
+    if skip >= total {
+        let before = catalog.problems.len();
+        catalog.problems.retain(|a| listed.contains(&a.question_id));
+        changes.removed = before - catalog.problems.len();
+    }
+    catalog.synced = Some(Utc::now());
+    catalog.write(repo)?;
+    Ok((catalog, changes))
+}
+
+/// Picks one of `problems` at random.
+fn pick<'a>(problems: &[&'a ListedProblem]) -> Option<&'a ListedProblem> {
+    if problems.is_empty() {
+        return None;
+    }
+    let mut hasher = std::collections::hash_map::RandomState::new().build_hasher();
+    hasher.write_u128(Utc::now().timestamp_nanos_opt().unwrap_or(0) as u128);
+    Some(problems[hasher.finish() as usize % problems.len()])
+}
+
+pub async fn catalog_subcommands(repo: &Repo, cmd: &CatalogCommand) -> Result<()> {
+    match cmd {
+        CatalogCommand::Sync { full } => {
+            let (catalog, changes) = sync(repo, *full).await?;
+            println!(
+                "The catalog has {} problems ({} new, {} updated, {} removed)",
+                catalog.problems.len(),
+                changes.added,
+                changes.updated,
+                changes.removed
+            );
+        }
+        CatalogCommand::Lookup { num } => {
+            let catalog = Catalog::load(repo)?;
+            match catalog.by_number(*num) {
+                Some(prob) => {
+                    println!("{}. {}", prob.frontend_id, prob.title);
+                    println!("Slug: {}", prob.title_slug);
+                    println!("Link: {}", prob.link());
+                }
+                None => {
+                    return Err(Error::not_found(format!(
+                        "Problem {num} is not in the catalog, try `lc catalog sync`"
+                    ))
+                    .into())
+                }
+            }
+        }
+        CatalogCommand::Random {
+            difficulty,
+            tag,
+            no_premium,
+            start,
+        } => {
+            let catalog = Catalog::load(repo)?;
+            let filter = BrowseFilter {
+                difficulty: *difficulty,
+                tag: tag.clone(),
+                exclude_premium: *no_premium,
+                ..Default::default()
+            };
+            let tracked = tracked_problems(repo)?;
+            let candidates = catalog
+                .filter(&filter)
+                .into_iter()
+                .filter(|a| !tracked.contains(&a.frontend_id))
+                .collect::<Vec<_>>();
+            let Some(prob) = pick(&candidates) else {
+                return Err(Error::not_found("No untracked problem matches").into());
+            };
+            let difficulty = prob
+                .difficulty
+                .map(|a| a.to_string())
+                .unwrap_or("Unknown".to_owned());
+            println!("{}. {} ({difficulty})", prob.frontend_id, prob.title);
+            println!("Link: {}", prob.link());
+            if *start {
+                super::new::run(repo, &prob.link()).await?;
+            }
+        }
+    }
+    Ok(())
+}
@@ -1,12 +1,26 @@
 // common code goes here
 use anyhow::Result;
 use clap::{Parser, Subcommand};
-use std::{error::Error, fmt::Debug, io::Write, str::FromStr};
+use std::{
+    fmt::Debug,
+    io::{IsTerminal, Write},
+    path::PathBuf,
+    str::FromStr,
+};
+use strum::Display;
 
+use super::browse::RemoteStatus;
+use super::catalog::CatalogCommand;
+use super::error::Error;
+use super::export::ExportFormat;
+use super::import::ImportCommand;
+use super::info::Difficulty;
+use super::repo::Repo;
 use super::search::SearchCommand;
 use super::tag::TagCommand;
 
 pub const GQL_ENDPOINT: &str = "https://leetcode.com/graphql/";
+pub const LEETCODE_HOST: &str = "leetcode.com";
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -15,6 +29,12 @@ pub const GQL_ENDPOINT: &str = "https://leetcode.com/graphql/";
 pub struct Cli {
     #[command(subcommand)]
     pub command: Commands,
+    /// Answer from responses cached earlier instead of asking LeetCode
+    #[arg(long, global = true)]
+    pub offline: bool,
+    /// Ask LeetCode even when a cached response is still fresh
+    #[arg(long, global = true, conflicts_with = "offline")]
+    pub refresh: bool,
 }
 
 #[derive(Subcommand, Debug)]
@@ -22,15 +42,29 @@ pub enum Commands {
     /// Create a new problem to be tracked
     #[command(arg_required_else_help = true)]
     New {
-        /// A link to the specific LeetCode Problem
-        link: String,
+        /// Links to the problems, or their numbers, slugs or titles
+        problems: Vec<String>,
+        /// A file listing more problems, one per line
+        #[arg(short, long)]
+        file: Option<PathBuf>,
     },
     /// Edit a solution for the given problem number
     #[command(arg_required_else_help = true)]
-    Edit { num: usize },
+    Edit {
+        num: usize,
+        /// Rerun the problem's tests every time the solution is saved
+        #[arg(short, long)]
+        watch: bool,
+        /// Only watch, without starting the editor, to edit the solution from somewhere else
+        #[arg(long, requires = "watch")]
+        no_editor: bool,
+    },
     /// Add, Remove, Search, or Edit tags to problems
     #[command(arg_required_else_help = true)]
-    Tag { cmd: TagCommand },
+    Tag {
+        #[command(subcommand)]
+        cmd: TagCommand,
+    },
     /// Get information about problem status, tags, and solutions
     #[command(arg_required_else_help = true)]
     Info { num: usize },
@@ -49,26 +83,340 @@ pub enum Commands {
     /// Tag a problem as completed
     #[command(arg_required_else_help = true)]
     Finish { num: usize },
+    /// Page through the problems on LeetCode and pick ones to start tracking
+    Browse {
+        /// easy, medium or hard
+        #[arg(short, long)]
+        difficulty: Option<Difficulty>,
+        /// The slug of a topic tag, e.g. dynamic-programming
+        #[arg(short, long)]
+        tag: Option<String>,
+        /// todo, solved or attempted on the LeetCode account
+        #[arg(short, long)]
+        status: Option<RemoteStatus>,
+        /// Only problems matching these words
+        #[arg(short, long)]
+        keyword: Option<String>,
+        /// Leave out premium problems
+        #[arg(long)]
+        no_premium: bool,
+        #[arg(short, long, default_value_t = 1, value_parser = at_least_one())]
+        page: usize,
+        #[arg(long, default_value_t = 20, value_parser = at_least_one())]
+        per_page: usize,
+        /// Ask LeetCode even when there is a catalog to browse offline
+        #[arg(long)]
+        online: bool,
+    },
+    /// Keep a copy of LeetCode's problem list in the repo to use offline
+    #[command(arg_required_else_help = true)]
+    Catalog {
+        #[command(subcommand)]
+        cmd: CatalogCommand,
+    },
+    /// Start tracking problems that were solved before the repo existed
+    #[command(arg_required_else_help = true)]
+    Import {
+        #[command(subcommand)]
+        cmd: ImportCommand,
+    },
+    /// Show today's daily challenge
+    Daily {
+        /// Start tracking the daily challenge like `new` would
+        #[arg(long)]
+        start: bool,
+    },
+    /// List the finished problems due for review, or grade the review of one
+    Review {
+        /// The problem that was re-solved, lists what is due when left out
+        num: Option<usize>,
+        /// Start timing a re-solve of the problem instead of grading it
+        #[arg(long)]
+        start: bool,
+        /// Recall grade from 0 (forgot) to 5 (perfect), worked out from the tests and time if left out
+        #[arg(short, long)]
+        grade: Option<u8>,
+    },
+    /// Show progress across every tracked problem
+    Stats {
+        /// Print the statistics as JSON instead of tables
+        #[arg(long)]
+        json: bool,
+    },
+    /// Show the commits lc made for new, finished and tagged problems
+    Log {
+        /// Only the commits for this problem
+        num: Option<usize>,
+        /// How many commits to show
+        #[arg(short = 'n', long, default_value_t = 20)]
+        limit: usize,
+    },
+    /// Regenerate the progress section of the repo's top-level README
+    Readme,
+    /// Write out every tracked problem to share progress
+    Export {
+        /// csv, json or md
+        #[arg(short, long, default_value = "csv")]
+        format: ExportFormat,
+        /// Write to this file instead of printing
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
 }
 
-pub fn prompt_for_input<T: 'static>(prompt: &str) -> Result<(String, T)>
+/// Parses a count that must be at least one, e.g. a page number.
+fn at_least_one() -> clap::builder::RangedU64ValueParser<usize> {
+    clap::builder::RangedU64ValueParser::new().range(1..)
+}
+
+/// How far along a local test run or a LeetCode judgement is, for showing progress.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display)]
+pub enum Stage {
+    Queued,
+    Compiling,
+    Running,
+}
+
+pub fn prompt_for_input<T>(prompt: &str) -> Result<(String, T)>
 where
-    T: Debug + FromStr,
-    <T as FromStr>::Err: Send + Sync + Error,
+    T: 'static + Debug + FromStr,
+    <T as FromStr>::Err: Into<anyhow::Error>,
 {
     print!("{}", prompt);
     std::io::stdout().flush()?;
     let mut input = String::from("");
     std::io::stdin().read_line(&mut input)?;
     let input = input.trim().to_string();
-    let output = input.parse::<T>()?;
+    let output = input.parse::<T>().map_err(Into::into)?;
     // println!("Input was parsed from: {} into {:?}", input, output);
     Ok((input, output))
 }
 
-pub fn get_lc_dir() -> Result<String> {
-    use std::env;
-    let key = "LEETCODE_DIR";
-    // val is the top level directory for the leetcode directory
-    env::var(key).map_err(|e| e.into())
+/// Returns `arg` when it was given on the command line, otherwise falls back to prompting the
+/// user. Prompting only happens when stdin is a terminal so that scripts fail instead of hanging.
+pub fn arg_or_prompt<T>(arg: Option<T>, prompt: &str) -> Result<T>
+where
+    T: 'static + Debug + FromStr,
+    <T as FromStr>::Err: Into<anyhow::Error>,
+{
+    match arg {
+        Some(a) => Ok(a),
+        None => {
+            if !std::io::stdin().is_terminal() {
+                return Err(anyhow::Error::msg(format!(
+                    "Missing argument and stdin is not a terminal to prompt with: {}",
+                    prompt.trim_end_matches([':', ' '])
+                )));
+            }
+            let (_input, out) = prompt_for_input::<T>(prompt)?;
+            Ok(out)
+        }
+    }
+}
+
+/// Same as `arg_or_prompt` but for a list of whitespace separated values, where an empty list
+/// counts as missing.
+pub fn args_or_prompt<T>(args: &[T], prompt: &str) -> Result<Vec<T>>
+where
+    T: 'static + Debug + Clone + FromStr,
+    <T as FromStr>::Err: Into<anyhow::Error>,
+{
+    if !args.is_empty() {
+        return Ok(args.to_vec());
+    }
+    let input = arg_or_prompt::<String>(None, prompt)?;
+    let out = input
+        .split_whitespace()
+        .map(|a| a.parse::<T>().map_err(Into::into))
+        .collect::<Result<Vec<T>>>()?;
+    if out.is_empty() {
+        return Err(anyhow::Error::msg("No values were given"));
+    }
+    Ok(out)
+}
+
+/// Returns the numbers of every problem that is currently tracked in the repo, sorted.
+pub fn tracked_problems(repo: &Repo) -> Result<Vec<usize>> {
+    let mut out = Vec::new();
+    for path in repo.list(&repo.problems_dir())? {
+        if !repo.is_dir(&path) {
+            continue;
+        }
+        if let Some(num) = path
+            .file_name()
+            .and_then(|a| a.to_str())
+            .and_then(|a| a.parse::<usize>().ok())
+        {
+            out.push(num);
+        }
+    }
+    out.sort_unstable();
+    Ok(out)
+}
+
+/// A selection of problems given on the command line.
+///
+/// Accepts a single number (`42`), a range (`1..20` or `1..=20`), or a comma separated mix of
+/// those (`1,4,10..=15`). Ranges only select problems that are already tracked whereas single
+/// numbers must be tracked.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProblemSet {
+    parts: Vec<ProblemSetPart>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ProblemSetPart {
+    Single(usize),
+    // end is exclusive
+    Range(usize, usize),
+}
+
+impl FromStr for ProblemSet {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut parts = Vec::new();
+        for part in s.split(',').map(str::trim) {
+            let number = |a: &str| {
+                a.trim().parse::<usize>().map_err(|e| {
+                    Error::parse(format!("Not a problem number or range: `{part}`")).caused_by(e)
+                })
+            };
+            let parsed = if let Some((start, end)) = part.split_once("..=") {
+                let end = number(end)?
+                    .checked_add(1)
+                    .ok_or_else(|| Error::parse(format!("Range is too large: `{part}`")))?;
+                ProblemSetPart::Range(number(start)?, end)
+            } else if let Some((start, end)) = part.split_once("..") {
+                ProblemSetPart::Range(number(start)?, number(end)?)
+            } else {
+                ProblemSetPart::Single(number(part)?)
+            };
+            if let ProblemSetPart::Range(start, end) = parsed {
+                if start >= end {
+                    return Err(Error::parse(format!("Range is empty: `{part}`")).into());
+                }
+            }
+            parts.push(parsed);
+        }
+        Ok(ProblemSet { parts })
+    }
+}
+
+impl ProblemSet {
+    /// Resolves the selection against the problems in the repo, in ascending order without
+    /// duplicates.
+    pub fn resolve(&self, repo: &Repo) -> Result<Vec<usize>> {
+        self.resolve_from(&tracked_problems(repo)?)
+    }
+
+    /// Resolves the selection against an already known list of tracked problems.
+    pub fn resolve_from(&self, tracked: &[usize]) -> Result<Vec<usize>> {
+        let mut out = Vec::new();
+        for part in self.parts.iter() {
+            match part {
+                ProblemSetPart::Single(num) => {
+                    if !tracked.contains(num) {
+                        return Err(Error::NotTracked(*num).into());
+                    }
+                    out.push(*num);
+                }
+                ProblemSetPart::Range(start, end) => out.extend(
+                    tracked
+                        .iter()
+                        .filter(|num| (*start..*end).contains(*num))
+                        .copied(),
+                ),
+            }
+        }
+        out.sort_unstable();
+        out.dedup();
+        Ok(out)
+    }
+}
+
+/// Returns the editor command to open files with, split into the program and its arguments.
+/// Uses `$LEETCODE_EDITOR`, then `$EDITOR`, and falls back to `vi`.
+pub fn get_editor() -> Vec<String> {
+    let editor = std::env::var("LEETCODE_EDITOR")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_default();
+    let mut out = editor
+        .split_whitespace()
+        .map(str::to_owned)
+        .collect::<Vec<_>>();
+    if out.is_empty() {
+        out.push("vi".to_owned());
+    }
+    out
+}
+
+/// Whether `editor` draws in the terminal it is started from, so nothing else can print there
+/// while it runs. Emacs only does with `-nw`, `-t` or `--tty`.
+pub fn is_terminal_editor(editor: &[String]) -> bool {
+    let name = std::path::Path::new(&editor[0])
+        .file_name()
+        .and_then(|a| a.to_str())
+        .unwrap_or_default();
+    match name {
+        "vi" | "vim" | "nvim" | "nano" | "pico" | "hx" | "helix" | "kak" | "micro" | "ne"
+        | "joe" | "mg" | "ed" => true,
+        "emacs" | "emacsclient" => editor[1..]
+            .iter()
+            .any(|a| matches!(a.as_str(), "-nw" | "-t" | "--tty" | "--no-window-system")),
+        _ => false,
+    }
+}
+
+/// Fails when the editor didn't exit cleanly, so a crashed or aborted edit isn't taken as done.
+pub fn check_editor_exit(editor: &[String], status: std::process::ExitStatus) -> Result<()> {
+    if !status.success() {
+        return Err(anyhow::Error::msg(format!(
+            "Editor `{}` exited with {status}",
+            editor[0]
+        )));
+    }
+    Ok(())
+}
+
+/// Opens `path` in the user's editor and waits for it to close.
+pub fn open_in_editor(path: &std::path::Path) -> Result<()> {
+    let editor = get_editor();
+    let status = std::process::Command::new(&editor[0])
+        .args(&editor[1..])
+        .arg(path)
+        .status()
+        .map_err(|e| anyhow::Error::msg(format!("Could not launch `{}`: {e}", editor[0])))?;
+    check_editor_exit(&editor, status)
+}
+
+/// Finds the closest match to `input` among `candidates`, ignoring case.
+/// Returns `None` when nothing is close enough to be a plausible typo.
+pub fn suggest<'a, I>(input: &str, candidates: I) -> Option<&'a str>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let input = input.to_ascii_lowercase();
+    let max = (input.len() / 3).max(2);
+    candidates
+        .into_iter()
+        .map(|a| (edit_distance(&input, &a.to_ascii_lowercase()), a))
+        .filter(|(dist, _)| *dist <= max)
+        .min_by_key(|(dist, _)| *dist)
+        .map(|(_, a)| a)
+}
+
+/// Levenshtein distance between two strings.
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut prev = (0..=b.len()).collect::<Vec<_>>();
+    for (i, ca) in a.chars().enumerate() {
+        let mut curr = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != *cb);
+            curr[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(curr[j] + 1);
+        }
+        prev = curr;
+    }
+    prev[b.len()]
 }
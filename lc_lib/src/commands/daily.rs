@@ -0,0 +1,90 @@
+// the daily coding challenge on LeetCode
+use anyhow::Result;
+use chrono::NaiveDate;
+use reqwest::Url;
+
+use super::common::{tracked_problems, GQL_ENDPOINT, LEETCODE_HOST};
+use super::error::Error;
+use super::info::{Difficulty, Meta};
+use super::new::{generate_anonymous_client, query_endpoint};
+use super::repo::Repo;
+
+/// Today's daily challenge.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Daily {
+    pub date: NaiveDate,
+    pub number: usize,
+    pub title: String,
+    pub title_slug: String,
+    pub difficulty: Option<Difficulty>,
+    pub link: String,
+}
+
+impl Daily {
+    /// Parses the response of the `questionOfToday` query.
+    pub fn from_json(json: &serde_json::Value) -> Result<Daily> {
+        let challenge = &json["data"]["activeDailyCodingChallengeQuestion"];
+        let question = &challenge["question"];
+        let field = |value: &serde_json::Value, key: &str| match value[key].as_str() {
+            Some(a) => Ok(a.to_owned()),
+            None => Err(Error::parse(format!(
+                "Could not get the {key} of the daily challenge from JSON"
+            ))),
+        };
+        let title_slug = field(question, "titleSlug")?;
+        Ok(Daily {
+            date: field(challenge, "date")?.parse()?,
+            number: field(question, "questionFrontendId")?.parse()?,
+            title: field(question, "title")?,
+            difficulty: field(question, "difficulty")?.parse().ok(),
+            link: format!("https://{LEETCODE_HOST}/problems/{title_slug}/"),
+            title_slug,
+        })
+    }
+}
+
+/// Asks LeetCode for today's daily challenge, which doesn't need the account.
+pub async fn fetch() -> Result<Daily> {
+    let client = generate_anonymous_client(&Url::parse(&format!("https://{LEETCODE_HOST}/"))?)?;
+    let query = serde_json::json!({"query":"\n    query questionOfToday {\n  activeDailyCodingChallengeQuestion {\n    date\n    link\n    question {\n      questionFrontendId\n      title\n      titleSlug\n      difficulty\n    }\n  }\n}\n    ","variables":{},"operationName":"questionOfToday"});
+    let data = query_endpoint(&GQL_ENDPOINT.to_string(), &query, &client).await?;
+    Daily::from_json(&data)
+}
+
+/// Notes in the problem's `META.json` that it was the daily challenge on `date`.
+pub fn record(repo: &Repo, num: usize, date: NaiveDate) -> Result<()> {
+    let mut meta = Meta::read(repo, num)?;
+    if !meta.dailies.contains(&date) {
+        meta.dailies.push(date);
+        meta.dailies.sort();
+    }
+    meta.write(repo, num)
+}
+
+pub async fn run(repo: &Repo, start: bool) -> Result<()> {
+    let daily = fetch().await?;
+    println!("Daily challenge for {}", daily.date);
+    println!("{}. {}", daily.number, daily.title);
+    println!(
+        "Difficulty: {}",
+        daily
+            .difficulty
+            .map(|a| a.to_string())
+            .unwrap_or("Unknown".to_owned())
+    );
+    println!("Link: {}", daily.link);
+
+    let tracked = tracked_problems(repo)?.contains(&daily.number);
+    if !start {
+        if tracked {
+            println!("It is already tracked as Problem {}", daily.number);
+        }
+        return Ok(());
+    }
+    if tracked {
+        println!("Problem {} is already tracked", daily.number);
+    } else {
+        super::new::run(repo, &daily.link).await?;
+    }
+    record(repo, daily.number, daily.date)
+}
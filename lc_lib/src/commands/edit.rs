@@ -1,5 +1,125 @@
 use anyhow::Result;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
 
-pub fn run(_prob: &usize) -> Result<()> {
-    todo!();
+use super::common::{check_editor_exit, get_editor, is_terminal_editor, open_in_editor};
+use super::error::Error;
+use super::local::{latest_change, run_tests};
+use super::repo::Repo;
+
+/// How often the solution files are checked for changes while watching.
+const POLL_INTERVAL: Duration = Duration::from_millis(300);
+
+/// Opens the solution of problem `num` in the editor. With `watch` the problem's tests are rerun
+/// every time one of its files is saved, printed as they finish next to a graphical editor. A
+/// terminal editor would be drawn over, so the tests run in the background while it is open and
+/// their results are printed once it exits. With `no_editor` only the tests are run, for editing
+/// from somewhere else.
+pub fn run(repo: &Repo, prob: &usize, watch: bool, no_editor: bool) -> Result<()> {
+    let main = repo.main_rs(*prob);
+    if !repo.exists(&main) {
+        return Err(Error::NotTracked(*prob).into());
+    }
+
+    if !watch {
+        return open_in_editor(&main);
+    }
+    if no_editor {
+        println!("Watching Problem {prob} for changes, press Ctrl-C to stop");
+        return watch_tests(repo, *prob, || Ok(false), |a| println!("{a}"));
+    }
+
+    let editor = get_editor();
+    let mut child = std::process::Command::new(&editor[0])
+        .args(&editor[1..])
+        .arg(&main)
+        .spawn()
+        .map_err(|e| anyhow::Error::msg(format!("Could not launch `{}`: {e}", editor[0])))?;
+
+    if is_terminal_editor(&editor) {
+        let closed = AtomicBool::new(false);
+        let (watched, status) = std::thread::scope(|scope| {
+            let watcher = scope.spawn(|| {
+                let mut results = Vec::new();
+                let res = watch_tests(
+                    repo,
+                    *prob,
+                    || Ok(closed.load(Ordering::Relaxed)),
+                    |a| results.push(a),
+                );
+                (res, results)
+            });
+            let status = child.wait();
+            closed.store(true, Ordering::Relaxed);
+            (watcher.join(), status)
+        });
+        let (res, results) =
+            watched.map_err(|_| anyhow::Error::msg("Watching the tests stopped unexpectedly"))?;
+        for line in &results {
+            println!("{line}");
+        }
+        res?;
+        if results.is_empty() {
+            println!("No changes were saved, so no tests were run");
+        }
+        return check_editor_exit(&editor, status?);
+    }
+
+    println!("Watching Problem {prob} for changes, tests run on every save");
+    let started = Instant::now();
+    let mut waited = false;
+    let editor_closed = || {
+        if waited {
+            return Ok(false);
+        }
+        match child.try_wait()? {
+            // editors that hand the file to an already running instance exit straight away, in
+            // that case keep watching until interrupted instead of stopping with the editor
+            Some(status) if status.success() && started.elapsed() < Duration::from_secs(2) => {
+                waited = true;
+                println!("Editor returned immediately, press Ctrl-C to stop watching");
+                Ok(false)
+            }
+            Some(_) => Ok(true),
+            None => Ok(false),
+        }
+    };
+    if let Err(e) = watch_tests(repo, *prob, editor_closed, |a| println!("{a}")) {
+        // the editor may hold unsaved work, so it is left to be closed rather than killed
+        if child.try_wait()?.is_none() {
+            eprintln!("Stopped watching: {e:#}, waiting for the editor to close");
+            child.wait()?;
+        }
+        return Err(e);
+    }
+    check_editor_exit(&editor, child.wait()?)
+}
+
+/// Reruns the tests of problem `num` on every change to its solution, handing a line for each run
+/// to `report`, until `done` says to stop. A save made just before that is still tested.
+fn watch_tests(
+    repo: &Repo,
+    num: usize,
+    mut done: impl FnMut() -> Result<bool>,
+    mut report: impl FnMut(String),
+) -> Result<()> {
+    let src_dir = repo.solution_dir(num);
+    let mut last = latest_change(repo, &src_dir)?;
+    let mut runs = 0;
+    loop {
+        std::thread::sleep(POLL_INTERVAL);
+        let stop = done()?;
+
+        let change = latest_change(repo, &src_dir)?;
+        if change > last {
+            last = change;
+            runs += 1;
+            let summary = run_tests(repo, num)?;
+            let mark = if summary.success() { "PASS" } else { "FAIL" };
+            report(format!("[run {runs}] {mark}: {}", summary.compact()));
+        }
+        if stop {
+            return Ok(());
+        }
+    }
 }
@@ -0,0 +1,204 @@
+// the ways lc fails that callers, and the exit code of the cli, tell apart
+use std::path::PathBuf;
+use strum::{Display, EnumIter};
+
+/// What caused an `Error`, kept so the whole chain is reported.
+pub type Source = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+/// Failures of the library that are worth telling apart. They travel inside `anyhow::Error`
+/// like everything else, so they are found again with `downcast_ref` or `ErrorKind::of`.
+#[derive(Debug)]
+pub enum Error {
+    /// A setting is missing or invalid, e.g. `$LEETCODE_DIR` or the catalog not being synced
+    Config {
+        message: String,
+        source: Option<Source>,
+    },
+    /// LEETCODE_SESSION or LEETCODE_TOKEN is missing, wrong or expired
+    Auth {
+        message: String,
+        source: Option<Source>,
+    },
+    /// LeetCode couldn't be reached, kept failing or answered with something other than JSON
+    Network {
+        message: String,
+        source: Option<Source>,
+    },
+    /// A response, file or argument didn't have the expected shape
+    Parse {
+        message: String,
+        source: Option<Source>,
+    },
+    /// The problem needs a LeetCode Premium subscription
+    PremiumOnly,
+    /// The problem, submission or page doesn't exist
+    NotFound(String),
+    /// The problem is already in the repo
+    AlreadyExists(usize),
+    /// The problem isn't in the repo
+    NotTracked(usize),
+    /// Reading or writing a file of the repo or the cache failed
+    Filesystem {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+}
+
+impl Error {
+    pub fn config(message: impl Into<String>) -> Error {
+        Error::Config {
+            message: message.into(),
+            source: None,
+        }
+    }
+
+    pub fn auth(message: impl Into<String>) -> Error {
+        Error::Auth {
+            message: message.into(),
+            source: None,
+        }
+    }
+
+    pub fn network(message: impl Into<String>) -> Error {
+        Error::Network {
+            message: message.into(),
+            source: None,
+        }
+    }
+
+    pub fn parse(message: impl Into<String>) -> Error {
+        Error::Parse {
+            message: message.into(),
+            source: None,
+        }
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Error {
+        Error::NotFound(message.into())
+    }
+
+    pub fn filesystem(path: impl Into<PathBuf>, source: std::io::Error) -> Error {
+        Error::Filesystem {
+            path: path.into(),
+            source,
+        }
+    }
+
+    /// Attaches the error that caused this one, for the kinds that carry a message.
+    pub fn caused_by(mut self, cause: impl Into<Source>) -> Error {
+        if let Error::Config { source, .. }
+        | Error::Auth { source, .. }
+        | Error::Network { source, .. }
+        | Error::Parse { source, .. } = &mut self
+        {
+            *source = Some(cause.into());
+        }
+        self
+    }
+
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Error::Config { .. } => ErrorKind::Config,
+            Error::Auth { .. } => ErrorKind::Auth,
+            Error::Network { .. } => ErrorKind::Network,
+            Error::Parse { .. } => ErrorKind::Parse,
+            Error::PremiumOnly => ErrorKind::PremiumOnly,
+            Error::NotFound(_) => ErrorKind::NotFound,
+            Error::AlreadyExists(_) => ErrorKind::AlreadyExists,
+            Error::NotTracked(_) => ErrorKind::NotTracked,
+            Error::Filesystem { .. } => ErrorKind::Filesystem,
+        }
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Config { message, .. }
+            | Error::Auth { message, .. }
+            | Error::Network { message, .. }
+            | Error::Parse { message, .. }
+            | Error::NotFound(message) => write!(f, "{message}"),
+            Error::PremiumOnly => write!(f, "The problem is only for LeetCode Premium"),
+            Error::AlreadyExists(num) => write!(f, "Problem {num} already exists in the repo!"),
+            Error::NotTracked(num) => write!(f, "Problem {num} is not tracked in the repo!"),
+            Error::Filesystem { path, .. } => write!(f, "Could not access {}", path.display()),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Config { source, .. }
+            | Error::Auth { source, .. }
+            | Error::Network { source, .. }
+            | Error::Parse { source, .. } => source
+                .as_deref()
+                .map(|a| a as &(dyn std::error::Error + 'static)),
+            Error::Filesystem { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// The kind of an error without its details, which decides the exit code of the cli.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display, EnumIter)]
+#[strum(serialize_all = "kebab-case")]
+pub enum ErrorKind {
+    /// Anything that isn't one of the kinds below, e.g. a bad argument
+    Other,
+    Config,
+    Auth,
+    Network,
+    Parse,
+    PremiumOnly,
+    NotFound,
+    AlreadyExists,
+    NotTracked,
+    Filesystem,
+}
+
+impl ErrorKind {
+    /// Finds the kind of `error` from the first error in its chain that has one. Errors from the
+    /// libraries lc uses are sorted too, so a bare `?` still gets a sensible kind.
+    pub fn of(error: &anyhow::Error) -> ErrorKind {
+        for cause in error.chain() {
+            if let Some(a) = cause.downcast_ref::<Error>() {
+                return a.kind();
+            }
+            if cause.is::<reqwest::Error>() {
+                return ErrorKind::Network;
+            }
+            if cause.is::<std::io::Error>() {
+                return ErrorKind::Filesystem;
+            }
+            if cause.is::<serde_json::Error>()
+                || cause.is::<std::num::ParseIntError>()
+                || cause.is::<chrono::ParseError>()
+            {
+                return ErrorKind::Parse;
+            }
+            if cause.is::<std::env::VarError>() {
+                return ErrorKind::Config;
+            }
+        }
+        ErrorKind::Other
+    }
+
+    /// The exit code of the cli. 2 is left to clap for usage errors.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            ErrorKind::Other => 1,
+            ErrorKind::Config => 3,
+            ErrorKind::Auth => 4,
+            ErrorKind::Network => 5,
+            ErrorKind::Parse => 6,
+            ErrorKind::PremiumOnly => 7,
+            ErrorKind::NotFound => 8,
+            ErrorKind::AlreadyExists => 9,
+            ErrorKind::NotTracked => 10,
+            ErrorKind::Filesystem => 11,
+        }
+    }
+}
@@ -0,0 +1,181 @@
+// writing out progress for sharing outside of the repo
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::path::Path;
+use strum::{Display, EnumString};
+
+use super::info::{load_all, ProblemInfo};
+use super::repo::Repo;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display, EnumString)]
+#[strum(ascii_case_insensitive)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+    #[strum(serialize = "md", serialize = "markdown")]
+    Md,
+}
+
+/// One tracked problem as it is exported.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ExportRow {
+    pub number: usize,
+    pub title: String,
+    pub link: String,
+    pub difficulty: Option<String>,
+    pub tags: Vec<String>,
+    pub status: String,
+    pub created: Option<DateTime<Utc>>,
+    pub finished: Option<DateTime<Utc>>,
+    /// Hours from creating the problem to finishing it
+    pub hours_spent: Option<f64>,
+    /// The solution files, e.g. `main.rs` and `two_pointers.rs`
+    pub variants: Vec<String>,
+    /// The problem's directory relative to the top of the repo
+    pub directory: String,
+}
+
+impl ExportRow {
+    pub fn new(info: &ProblemInfo, variants: Vec<String>) -> ExportRow {
+        let meta = &info.meta;
+        let hours_spent = match (meta.created, meta.finished) {
+            (Some(created), Some(finished)) => {
+                Some(((finished - created).num_minutes() as f64 / 60.0).max(0.0))
+            }
+            _ => None,
+        };
+        ExportRow {
+            number: info.number,
+            title: info.title.clone(),
+            link: info.link.clone(),
+            difficulty: meta.difficulty.map(|a| a.to_string()),
+            tags: info.tags.iter().cloned().collect(),
+            status: meta.status.to_string(),
+            created: meta.created,
+            finished: meta.finished,
+            hours_spent,
+            variants,
+            directory: format!("src/{}/", info.number),
+        }
+    }
+}
+
+/// The solution files of problem `num`, sorted by name.
+fn variants(repo: &Repo, num: usize) -> Result<Vec<String>> {
+    Ok(repo
+        .list(&repo.solution_dir(num))?
+        .iter()
+        .filter(|a| !repo.is_dir(a))
+        .filter_map(|a| a.file_name())
+        .map(|a| a.to_string_lossy().to_string())
+        .collect())
+}
+
+/// Quotes a CSV field when it holds a separator, quote or line break.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+/// Escapes the characters that would break out of a Markdown table cell.
+fn md_cell(cell: &str) -> String {
+    cell.replace('|', "\\|").replace('\n', " ")
+}
+
+fn date(time: Option<DateTime<Utc>>) -> String {
+    time.map(|a| a.to_rfc3339()).unwrap_or_default()
+}
+
+fn hours(hours: Option<f64>) -> String {
+    hours.map(|a| format!("{a:.1}")).unwrap_or_default()
+}
+
+/// Writes `rows` out in `format`. CSV and JSON keep every field, Markdown is a table meant to be
+/// committed at the top of the repo so its links to the solutions resolve.
+pub fn render(rows: &[ExportRow], format: ExportFormat) -> Result<String> {
+    let mut out = String::new();
+    match format {
+        ExportFormat::Json => {
+            out = serde_json::to_string_pretty(rows)?;
+            out.push('\n');
+        }
+        ExportFormat::Csv => {
+            out.push_str(
+                "number,title,link,difficulty,tags,status,created,finished,hours_spent,variants\n",
+            );
+            for row in rows {
+                let fields = [
+                    row.number.to_string(),
+                    row.title.clone(),
+                    row.link.clone(),
+                    row.difficulty.clone().unwrap_or_default(),
+                    row.tags.join(";"),
+                    row.status.clone(),
+                    date(row.created),
+                    date(row.finished),
+                    hours(row.hours_spent),
+                    row.variants.join(";"),
+                ];
+                let fields = fields.iter().map(|a| csv_field(a)).collect::<Vec<_>>();
+                out.push_str(&fields.join(","));
+                out.push('\n');
+            }
+        }
+        ExportFormat::Md => {
+            out.push_str(
+                "| # | Problem | Difficulty | Tags | Status | Finished | Hours | Solution |\n",
+            );
+            out.push_str("|--:|---|---|---|---|---|--:|---|\n");
+            for row in rows {
+                let title = match row.link.is_empty() {
+                    true => md_cell(&row.title),
+                    false => format!("[{}]({})", md_cell(&row.title), row.link),
+                };
+                let solutions = match row.variants.as_slice() {
+                    [] => format!("[{}]({})", row.directory, row.directory),
+                    variants => variants
+                        .iter()
+                        .map(|a| format!("[{a}]({}src/{a})", row.directory))
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                };
+                out.push_str(&format!(
+                    "| {} | {title} | {} | {} | {} | {} | {} | {solutions} |\n",
+                    row.number,
+                    row.difficulty.as_deref().unwrap_or(""),
+                    md_cell(&row.tags.join(", ")),
+                    row.status,
+                    row.finished
+                        .map(|a| a.format("%Y-%m-%d").to_string())
+                        .unwrap_or_default(),
+                    hours(row.hours_spent),
+                ));
+            }
+        }
+    }
+    Ok(out)
+}
+
+/// Every tracked problem, ready to be exported.
+pub fn collect(repo: &Repo) -> Result<Vec<ExportRow>> {
+    Ok(load_all(repo)?
+        .iter()
+        .map(|a| ExportRow::new(a, variants(repo, a.number).unwrap_or_default()))
+        .collect())
+}
+
+pub fn run(repo: &Repo, format: ExportFormat, output: Option<&Path>) -> Result<()> {
+    let out = render(&collect(repo)?, format)?;
+    match output {
+        Some(path) => {
+            repo.write(path, &out)?;
+            println!("Exported to {}", path.display());
+        }
+        None => print!("{out}"),
+    }
+    Ok(())
+}
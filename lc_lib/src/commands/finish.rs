@@ -1 +1,11 @@
+use anyhow::Result;
 
+use super::info::{set_status, Status};
+use super::repo::Repo;
+
+/// Marks problem `num` as finished, with its `main.rs` as the solution.
+pub fn run(repo: &Repo, num: &usize) -> Result<()> {
+    set_status(repo, *num, Status::Finished)?;
+    println!("Problem: {num} was marked as finished");
+    Ok(())
+}
@@ -0,0 +1,64 @@
+// std::fs calls whose errors say which path they failed on
+use std::fs::ReadDir;
+use std::path::Path;
+
+use super::error::Error;
+
+fn at(path: &Path) -> impl FnOnce(std::io::Error) -> Error + '_ {
+    move |e| Error::filesystem(path, e)
+}
+
+pub fn read_to_string(path: impl AsRef<Path>) -> Result<String, Error> {
+    let path = path.as_ref();
+    std::fs::read_to_string(path).map_err(at(path))
+}
+
+/// Reads `path`, `None` when there is no such file.
+pub fn read_optional(path: impl AsRef<Path>) -> Result<Option<String>, Error> {
+    let path = path.as_ref();
+    match std::fs::read_to_string(path) {
+        Ok(a) => Ok(Some(a)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(Error::filesystem(path, e)),
+    }
+}
+
+pub fn write(path: impl AsRef<Path>, contents: impl AsRef<[u8]>) -> Result<(), Error> {
+    let path = path.as_ref();
+    std::fs::write(path, contents).map_err(at(path))
+}
+
+/// Writes `contents` next to `path` and renames it over the old file, so a failed write never
+/// leaves a partial file behind.
+pub fn replace(path: impl AsRef<Path>, contents: impl AsRef<[u8]>) -> Result<(), Error> {
+    let path = path.as_ref();
+    let mut new_path = path.as_os_str().to_owned();
+    new_path.push(".new");
+    write(&new_path, contents)?;
+    std::fs::rename(&new_path, path).map_err(at(path))
+}
+
+pub fn append(path: impl AsRef<Path>, contents: &str) -> Result<(), Error> {
+    use std::io::Write;
+    let path = path.as_ref();
+    std::fs::OpenOptions::new()
+        .append(true)
+        .open(path)
+        .and_then(|mut a| a.write_all(contents.as_bytes()))
+        .map_err(at(path))
+}
+
+pub fn create_dir_all(path: impl AsRef<Path>) -> Result<(), Error> {
+    let path = path.as_ref();
+    std::fs::create_dir_all(path).map_err(at(path))
+}
+
+pub fn read_dir(path: impl AsRef<Path>) -> Result<ReadDir, Error> {
+    let path = path.as_ref();
+    std::fs::read_dir(path).map_err(at(path))
+}
+
+pub fn remove_file(path: impl AsRef<Path>) -> Result<(), Error> {
+    let path = path.as_ref();
+    std::fs::remove_file(path).map_err(at(path))
+}
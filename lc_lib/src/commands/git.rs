@@ -0,0 +1,338 @@
+// committing what the tracker changes to the repo's git history
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+use std::process::Command;
+use strum::{Display, EnumString};
+
+use super::info::{load, load_all, Status};
+use super::readme;
+use super::repo::Repo;
+
+/// Set to anything but `0` to commit the files `new`, `finish` and `tag` change.
+pub const COMMIT_VAR: &str = "LEETCODE_GIT_COMMIT";
+/// Trailers added to every commit so `lc log` can find them whatever the template says.
+const ACTION_TRAILER: &str = "Lc-Action";
+const PROBLEM_TRAILER: &str = "Lc-Problem";
+
+/// A command whose changes are committed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display, EnumString)]
+#[strum(serialize_all = "lowercase")]
+pub enum Action {
+    New,
+    Finish,
+    Tag,
+}
+
+impl Action {
+    /// The commit message template, which can be replaced with `$LEETCODE_COMMIT_<ACTION>`.
+    /// `{num}`, `{title}` and `{details}` are filled in for each problem.
+    pub fn template(&self) -> String {
+        let default = match self {
+            Action::New => "new: {num}. {title}",
+            Action::Finish => "finish: {num}{details}",
+            Action::Tag => "tag: {num}{details}",
+        };
+        std::env::var(format!(
+            "LEETCODE_COMMIT_{}",
+            self.to_string().to_uppercase()
+        ))
+        .unwrap_or(default.to_owned())
+    }
+}
+
+pub fn enabled() -> bool {
+    std::env::var(COMMIT_VAR).is_ok_and(|a| !a.is_empty() && a != "0")
+}
+
+/// Runs git in the repo, returning what it printed.
+fn git(repo: &Repo, args: &[&str]) -> Result<String> {
+    let out = Command::new("git")
+        .arg("-C")
+        .arg(repo.root())
+        .args(args)
+        .output()
+        .map_err(|e| anyhow::Error::msg(format!("Could not run git: {e}")))?;
+    if !out.status.success() {
+        return Err(anyhow::Error::msg(format!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&out.stderr).trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&out.stdout).into_owned())
+}
+
+/// What a commit message can say about one problem.
+#[derive(Debug, Clone, PartialEq)]
+struct ProblemState {
+    status: Status,
+    tags: Vec<String>,
+    finished: Option<DateTime<Utc>>,
+}
+
+/// The state of every problem before a command runs, to tell what it changed afterwards.
+#[derive(Debug, Clone, Default)]
+pub struct Snapshot {
+    problems: BTreeMap<usize, ProblemState>,
+    /// Files that already had uncommitted changes, relative to the top of the repo
+    dirty: BTreeSet<String>,
+}
+
+impl Snapshot {
+    pub fn take(repo: &Repo) -> Result<Snapshot> {
+        Ok(Snapshot {
+            problems: load_all(repo)?
+                .into_iter()
+                .map(|a| {
+                    let state = ProblemState {
+                        status: a.meta.status,
+                        tags: a.tags.iter().cloned().collect(),
+                        finished: a.meta.finished,
+                    };
+                    (a.number, state)
+                })
+                .collect(),
+            dirty: BTreeSet::new(),
+        })
+    }
+}
+
+/// Checks that the commit made after the command would only hold its own changes and takes a
+/// snapshot to compare against. `None` when committing is turned off.
+pub fn prepare(repo: &Repo) -> Result<Option<Snapshot>> {
+    if !enabled() {
+        return Ok(None);
+    }
+    let staged = git(repo, &["diff", "--cached", "--name-only"])?;
+    if !staged.trim().is_empty() {
+        return Err(anyhow::Error::msg(format!(
+            "Refusing to run with changes already staged, commit or unstage them first:\n{}",
+            staged.trim_end()
+        )));
+    }
+    let dirty = git(
+        repo,
+        &[
+            "ls-files",
+            "--modified",
+            "--deleted",
+            "--others",
+            "--exclude-standard",
+        ],
+    )?;
+    Ok(Some(Snapshot {
+        dirty: dirty.lines().map(str::to_owned).collect(),
+        ..Snapshot::take(repo)?
+    }))
+}
+
+/// The files `action` writes for problem `num`, which are all that is staged for it.
+pub fn touched(repo: &Repo, action: Action, num: usize) -> Vec<PathBuf> {
+    match action {
+        Action::New => vec![
+            repo.main_rs(num),
+            repo.problem_readme(num),
+            repo.tags(num),
+            repo.meta(num),
+        ],
+        Action::Finish => vec![repo.meta(num)],
+        Action::Tag => vec![repo.tags(num)],
+    }
+}
+
+/// What the details of a tag change look like in a commit message, e.g. ` (+Array, -Stack)`.
+pub fn tag_details(before: &[String], after: &[String]) -> String {
+    let changes = after
+        .iter()
+        .filter(|a| !before.contains(a))
+        .map(|a| format!("+{a}"))
+        .chain(
+            before
+                .iter()
+                .filter(|a| !after.contains(a))
+                .map(|a| format!("-{a}")),
+        )
+        .collect::<Vec<_>>();
+    match changes.is_empty() {
+        true => String::new(),
+        false => format!(" ({})", changes.join(", ")),
+    }
+}
+
+/// Fills in `template` for one problem.
+pub fn render(template: &str, num: usize, title: &str, details: &str) -> String {
+    template
+        .replace("{num}", &num.to_string())
+        .replace("{title}", title)
+        .replace("{details}", details)
+}
+
+/// The full commit message for `lines`, one rendered template per problem. A single problem is
+/// the subject, several are listed in the body.
+pub fn message(action: Action, nums: &[usize], lines: &[String]) -> String {
+    let mut out = match lines {
+        [line] => line.clone(),
+        _ => format!("{action}: {} problems\n\n{}", lines.len(), lines.join("\n")),
+    };
+    let nums = nums.iter().map(|a| a.to_string()).collect::<Vec<_>>();
+    out.push_str(&format!(
+        "\n\n{ACTION_TRAILER}: {action}\n{PROBLEM_TRAILER}: {}\n",
+        nums.join(", ")
+    ));
+    out
+}
+
+/// Commits what `action` changed since `before`, staging only the files it touches. Nothing is
+/// committed when one of them already had changes of its own, since those would go in with it.
+pub fn commit(repo: &Repo, action: Action, before: &Snapshot) -> Result<()> {
+    let after = Snapshot::take(repo)?;
+    let mut nums = Vec::new();
+    let mut lines = Vec::new();
+    let mut paths = Vec::new();
+    for (num, state) in &after.problems {
+        let was = before.problems.get(num);
+        let details = match action {
+            Action::New if was.is_none() => String::new(),
+            Action::Finish
+                if state.status == Status::Finished
+                    && was.map(|a| a.status) != Some(Status::Finished) =>
+            {
+                // finishing doesn't submit, so the verdict is the accepted `lc submit` that came
+                // since the problem was last finished, if there is one
+                let since = was.and_then(|a| a.finished);
+                load(repo, *num)?
+                    .meta
+                    .last_submission
+                    .filter(|a| a.status == "Accepted" && since.is_none_or(|b| a.submitted > b))
+                    .map(|a| match a.runtime {
+                        Some(runtime) => format!(" ({}, {runtime})", a.status),
+                        None => format!(" ({})", a.status),
+                    })
+                    .unwrap_or_default()
+            }
+            Action::Tag => match was {
+                Some(old) if old.tags != state.tags => tag_details(&old.tags, &state.tags),
+                _ => continue,
+            },
+            _ => continue,
+        };
+        lines.push(render(
+            &action.template(),
+            *num,
+            &load(repo, *num)?.title,
+            &details,
+        ));
+        nums.push(*num);
+        paths.extend(touched(repo, action, *num));
+    }
+    if nums.is_empty() {
+        return Ok(());
+    }
+    if action == Action::New {
+        paths.push(repo.cargo_toml());
+    }
+    if readme::hook_enabled() {
+        paths.push(repo.readme());
+    }
+
+    let paths = paths
+        .iter()
+        .filter(|a| repo.exists(a))
+        .map(|a| repo.relative(a).to_string_lossy().into_owned())
+        .collect::<Vec<_>>();
+    let dirty = paths
+        .iter()
+        .filter(|a| before.dirty.contains(*a))
+        .cloned()
+        .collect::<Vec<_>>();
+    if !dirty.is_empty() {
+        return Err(anyhow::Error::msg(format!(
+            "Not committing, these files had uncommitted changes before the command ran:\n{}",
+            dirty.join("\n")
+        )));
+    }
+    let mut args = vec!["add", "--"];
+    args.extend(paths.iter().map(String::as_str));
+    git(repo, &args)?;
+    if git(repo, &["diff", "--cached", "--name-only"])?
+        .trim()
+        .is_empty()
+    {
+        return Ok(());
+    }
+    git(
+        repo,
+        &["commit", "-q", "-m", &message(action, &nums, &lines)],
+    )?;
+    println!("Committed: {}", lines.join(", "));
+    Ok(())
+}
+
+/// Commits after a command that already succeeded, so a failure is only reported.
+pub fn hook(repo: &Repo, action: Action, before: Option<Snapshot>) {
+    if let Some(before) = before {
+        if let Err(e) = commit(repo, action, &before) {
+            println!("Could not commit the changes: {e}");
+        }
+    }
+}
+
+/// A commit made by the tracker.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogEntry {
+    pub hash: String,
+    /// The day it was committed, e.g. `2024-03-01`
+    pub date: String,
+    pub subject: String,
+    pub action: Option<Action>,
+    pub problems: Vec<usize>,
+}
+
+/// Fields and records are split with the ASCII unit and record separators.
+const LOG_FORMAT: &str = "--format=%h%x1f%as%x1f%s%x1f%(trailers:key=Lc-Action,valueonly,separator=%x2C)%x1f%(trailers:key=Lc-Problem,valueonly,separator=%x2C)%x1e";
+
+/// Parses `git log` output in `LOG_FORMAT`.
+pub fn parse_log(output: &str) -> Vec<LogEntry> {
+    output
+        .split('\x1e')
+        .filter_map(|record| {
+            let fields = record.trim().split('\x1f').collect::<Vec<_>>();
+            let [hash, date, subject, action, problems] = fields.as_slice() else {
+                return None;
+            };
+            Some(LogEntry {
+                hash: hash.to_string(),
+                date: date.to_string(),
+                subject: subject.to_string(),
+                action: action.trim().parse().ok(),
+                problems: problems
+                    .split(',')
+                    .filter_map(|a| a.trim().parse().ok())
+                    .collect(),
+            })
+        })
+        .collect()
+}
+
+/// The tracker's commits, newest first, only the ones touching problem `num` when given.
+pub fn log(repo: &Repo, num: Option<usize>) -> Result<Vec<LogEntry>> {
+    let grep = format!("--grep=^{ACTION_TRAILER}: ");
+    let output = git(repo, &["log", &grep, LOG_FORMAT])?;
+    Ok(parse_log(&output)
+        .into_iter()
+        .filter(|a| num.is_none_or(|num| a.problems.contains(&num)))
+        .collect())
+}
+
+pub fn run_log(repo: &Repo, num: Option<usize>, limit: usize) -> Result<()> {
+    let entries = log(repo, num)?;
+    if entries.is_empty() {
+        println!("No commits from lc yet, set ${COMMIT_VAR} to make them");
+    }
+    for entry in entries.iter().take(limit) {
+        println!("{} {} {}", entry.hash, entry.date, entry.subject);
+    }
+    Ok(())
+}
@@ -1 +1,11 @@
+use anyhow::Result;
 
+use super::info::{set_status, Status};
+use super::repo::Repo;
+
+/// Marks problem `num` as attempted but put aside for later.
+pub fn run(repo: &Repo, num: &usize) -> Result<()> {
+    set_status(repo, *num, Status::Hidden)?;
+    println!("Problem: {num} was hidden");
+    Ok(())
+}
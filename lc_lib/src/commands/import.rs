@@ -0,0 +1,552 @@
+// bringing problems solved before the repo existed into it
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use clap::Subcommand;
+use regex::Regex;
+use reqwest::Url;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use super::browse::ListedProblem;
+use super::catalog::Catalog;
+use super::common::{tracked_problems, GQL_ENDPOINT, LEETCODE_HOST};
+use super::error::Error;
+use super::fs;
+use super::info::{load_all, Meta, Status};
+use super::link::ProblemRef;
+use super::new::{
+    add_bin, create_entry, fetch_problem, find_problem, generate_request_client, has_bin,
+    query_endpoint,
+};
+use super::repo::Repo;
+
+/// How many submissions are asked for at once.
+const SUBMISSION_PAGE: usize = 20;
+/// The language solutions in the repo are written in, as LeetCode names it.
+const REPO_LANG: &str = "rust";
+
+#[derive(Subcommand, Debug)]
+pub enum ImportCommand {
+    /// Track every problem with an accepted Rust submission on the LeetCode account
+    Remote,
+    /// Move the solutions in another folder into the repo, e.g. `0001-two-sum.rs` or
+    /// `problems/two_sum/mod.rs`
+    #[command(arg_required_else_help = true)]
+    Dir {
+        path: PathBuf,
+        /// Only show which problem each file was matched to
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+/// A submission as listed by the `submissionList` query.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Submission {
+    pub id: u64,
+    pub title: String,
+    pub title_slug: String,
+    /// The language slug, e.g. `rust` or `python3`
+    pub lang: String,
+    pub accepted: bool,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl Submission {
+    pub fn from_json(json: &serde_json::Value) -> Result<Submission> {
+        let field = |key: &str| match json[key].as_str() {
+            Some(a) => Ok(a.to_owned()),
+            None => Err(Error::parse(format!(
+                "Could not get the {key} of a submission from JSON"
+            ))),
+        };
+        let timestamp = field("timestamp")?.parse::<i64>()?;
+        Ok(Submission {
+            id: field("id")?.parse()?,
+            title: field("title")?,
+            title_slug: field("titleSlug")?,
+            lang: field("lang")?,
+            accepted: field("statusDisplay")? == "Accepted",
+            timestamp: match DateTime::from_timestamp(timestamp, 0) {
+                Some(a) => a,
+                None => {
+                    return Err(
+                        Error::parse(format!("{timestamp} is not a valid submission time")).into(),
+                    )
+                }
+            },
+        })
+    }
+}
+
+/// A problem that was accepted on LeetCode, worked out from every submission made to it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Solved {
+    pub title: String,
+    pub title_slug: String,
+    /// The first submission of any kind, standing in for when the problem was started
+    pub started: DateTime<Utc>,
+    /// The first accepted submission
+    pub finished: DateTime<Utc>,
+    /// The most recent accepted submission, whose code becomes the solution
+    pub last_accepted: u64,
+}
+
+/// Groups `submissions` by problem, keeping the problems with an accepted submission in
+/// `REPO_LANG`, oldest first. Submissions in other languages still count towards when a problem
+/// was started.
+pub fn solved(submissions: &[Submission]) -> Vec<Solved> {
+    let mut by_slug: BTreeMap<&str, Vec<&Submission>> = BTreeMap::new();
+    for sub in submissions {
+        by_slug.entry(&sub.title_slug).or_default().push(sub);
+    }
+    let mut out = by_slug
+        .into_values()
+        .filter_map(|mut subs| {
+            subs.sort_by_key(|a| (a.timestamp, a.id));
+            let accepted = subs
+                .iter()
+                .filter(|a| a.accepted && a.lang == REPO_LANG)
+                .collect::<Vec<_>>();
+            let (first, last) = (accepted.first()?, accepted.last()?);
+            Some(Solved {
+                title: first.title.clone(),
+                title_slug: first.title_slug.clone(),
+                started: subs[0].timestamp,
+                finished: first.timestamp,
+                last_accepted: last.id,
+            })
+        })
+        .collect::<Vec<_>>();
+    out.sort_by_key(|a| a.finished);
+    out
+}
+
+/// Every submission on the LeetCode account, newest first.
+pub async fn fetch_submissions(client: &reqwest::Client) -> Result<Vec<Submission>> {
+    let mut out = Vec::new();
+    let mut last_key = serde_json::Value::Null;
+    loop {
+        let query = serde_json::json!({"query":"\n    query submissionList($offset: Int!, $limit: Int!, $lastKey: String, $questionSlug: String) {\n  submissionList(\n    offset: $offset\n    limit: $limit\n    lastKey: $lastKey\n    questionSlug: $questionSlug\n  ) {\n    lastKey\n    hasNext\n    submissions {\n      id\n      statusDisplay\n      lang\n      timestamp\n      title\n      titleSlug\n    }\n  }\n}\n    ","variables":{"offset":out.len(),"limit":SUBMISSION_PAGE,"lastKey":last_key,"questionSlug":""},"operationName":"submissionList"});
+        let data = query_endpoint(&GQL_ENDPOINT.to_string(), &query, client).await?;
+        let list = &data["data"]["submissionList"];
+        let Some(submissions) = list["submissions"].as_array() else {
+            return Err(Error::parse(
+                "Could not get the submissionList from JSON, is LEETCODE_SESSION still valid?",
+            )
+            .into());
+        };
+        for sub in submissions {
+            out.push(Submission::from_json(sub)?);
+        }
+        if submissions.is_empty() || !list["hasNext"].as_bool().unwrap_or(false) {
+            return Ok(out);
+        }
+        last_key = list["lastKey"].clone();
+    }
+}
+
+/// The code of submission `id`.
+pub async fn fetch_code(client: &reqwest::Client, id: u64) -> Result<String> {
+    let query = serde_json::json!({"query":"\n    query submissionDetails($submissionId: Int!) {\n  submissionDetails(submissionId: $submissionId) {\n    code\n  }\n}\n    ","variables":{"submissionId":id},"operationName":"submissionDetails"});
+    let data = query_endpoint(&GQL_ENDPOINT.to_string(), &query, client).await?;
+    match data["data"]["submissionDetails"]["code"].as_str() {
+        Some(a) => Ok(a.to_owned()),
+        None => Err(Error::parse(format!(
+            "Could not get the code of submission {id} from JSON"
+        ))
+        .into()),
+    }
+}
+
+/// Scaffolds `solved` like `lc new` does, with the accepted code as its solution, and marks it
+/// finished when it was first accepted. Returns the problem number, or `None` when it turned out
+/// to be tracked already.
+async fn import_solved(
+    repo: &Repo,
+    client: &reqwest::Client,
+    solved: &Solved,
+) -> Result<Option<usize>> {
+    let mut prob = fetch_problem(repo, &solved.title_slug).await?;
+    let num = prob.number();
+    // problems from before META.json recorded slugs are only found by their number
+    if tracked_problems(repo)?.contains(&num) {
+        return Ok(None);
+    }
+    prob.set_solution(fetch_code(client, solved.last_accepted).await?);
+    create_entry(repo, prob)?;
+    let mut meta = Meta::read(repo, num)?;
+    meta.status = Status::Finished;
+    meta.created = Some(solved.started);
+    meta.finished = Some(solved.finished);
+    meta.write(repo, num)?;
+    Ok(Some(num))
+}
+
+/// Imports every problem accepted on the LeetCode account that isn't tracked yet.
+pub async fn remote(repo: &Repo) -> Result<()> {
+    let client = generate_request_client(&Url::parse(&format!(
+        "https://{LEETCODE_HOST}/submissions/"
+    ))?)?;
+    let submissions = fetch_submissions(&client).await?;
+    let solved = solved(&submissions);
+    let tracked = load_all(repo)?
+        .into_iter()
+        .filter_map(|a| a.meta.title_slug)
+        .collect::<Vec<_>>();
+    let (skipped, todo): (Vec<_>, Vec<_>) = solved
+        .into_iter()
+        .partition(|a| tracked.contains(&a.title_slug));
+    println!(
+        "Found {} accepted problem(s), {} already tracked",
+        todo.len() + skipped.len(),
+        skipped.len()
+    );
+
+    let (mut imported, mut skipped) = (0, skipped.len());
+    let mut failed = Vec::new();
+    for (i, solved) in todo.iter().enumerate() {
+        let progress = format!("[{}/{}]", i + 1, todo.len());
+        match import_solved(repo, &client, solved).await {
+            Ok(Some(num)) => {
+                println!("{progress} Imported Problem {num}");
+                imported += 1;
+            }
+            Ok(None) => {
+                println!("{progress} {} is already tracked", solved.title);
+                skipped += 1;
+            }
+            Err(e) => {
+                println!("{progress} Failed {}: {e}", solved.title);
+                failed.push(solved.title.as_str());
+            }
+        }
+    }
+    println!(
+        "Imported: {imported}, skipped: {skipped}, failed: {}",
+        failed.len()
+    );
+    if failed.is_empty() {
+        Ok(())
+    } else {
+        Err(anyhow::Error::msg(format!(
+            "Failed to import: {}",
+            failed.join(", ")
+        )))
+    }
+}
+
+/// What a solution file says about which problem it solves.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Identity {
+    Number(usize),
+    /// A title slug, or a name that is close to one like `two_sum`
+    Slug(String),
+    /// A `<num>. <title>` header line, only trusted when the title is the problem's
+    Titled {
+        num: usize,
+        title: String,
+    },
+}
+
+/// File names that say nothing about the problem, so the directory they are in is used instead.
+const GENERIC_NAMES: [&str; 4] = ["mod", "main", "lib", "src"];
+/// How many lines at the top of a file are looked at for a header comment.
+const HEADER_LINES: usize = 10;
+
+/// Works out which problem the file at `path` solves, trying in order a link in the comments at
+/// the top of the file, a number in the file name (`0001-two-sum`, `p1`), a `<num>. <title>` line
+/// in those comments and finally the name itself (`two_sum`). Files named `mod.rs` or `main.rs`
+/// go by their directory instead.
+pub fn identify(path: &Path, contents: &str) -> Option<Identity> {
+    let link = Regex::new(r"leetcode\.com/[^\s)>\]]+").unwrap();
+    let titled =
+        Regex::new(r"^(?i:problem\s*|#\s*)?(?<num>\d+)\s*[.:)-]\s*(?<title>\w.*)$").unwrap();
+    let header = contents
+        .lines()
+        .take(HEADER_LINES)
+        .map(str::trim)
+        .take_while(|a| {
+            a.is_empty() || a.starts_with("//") || a.starts_with("/*") || a.starts_with('*')
+        })
+        .map(|a| a.trim_start_matches(['/', '*', '!']).trim())
+        .collect::<Vec<_>>();
+    if let Some(prob) = header.iter().find_map(|line| {
+        link.find(line)
+            .and_then(|a| ProblemRef::parse(a.as_str()).ok())
+    }) {
+        return Some(Identity::Slug(prob.slug));
+    }
+
+    let mut path = path.with_extension("");
+    while path
+        .file_name()
+        .and_then(|a| a.to_str())
+        .is_some_and(|a| GENERIC_NAMES.contains(&a))
+    {
+        path = path.parent()?.to_path_buf();
+    }
+    let name = path.file_name()?.to_str()?.to_lowercase();
+    let numbered = Regex::new(r"^(?:p|problem|lc)?[-_]?(?<num>\d+)(?:[-_. ].*)?$").unwrap();
+    if let Some(caps) = numbered.captures(&name) {
+        return caps["num"].parse().ok().map(Identity::Number);
+    }
+    // a note like `// 1. sort the input` looks the same, so the title is checked against the
+    // catalog before the number is believed
+    if let Some(caps) = header.iter().find_map(|line| titled.captures(line)) {
+        if let Ok(num) = caps["num"].parse() {
+            return Some(Identity::Titled {
+                num,
+                title: caps["title"].trim().to_owned(),
+            });
+        }
+    }
+    let slug = name.replace(['_', ' '], "-");
+    match slug.chars().any(|a| a.is_ascii_alphabetic()) {
+        true => Some(Identity::Slug(slug)),
+        false => None,
+    }
+}
+
+/// Whether two titles are the same once case and punctuation are ignored, so a slug matches too.
+fn same_title(a: &str, b: &str) -> bool {
+    let simple = |a: &str| {
+        a.chars()
+            .filter(|a| a.is_alphanumeric())
+            .flat_map(char::to_lowercase)
+            .collect::<String>()
+    };
+    simple(a) == simple(b)
+}
+
+/// Turns the contents of an old solution file into a `main.rs` that builds as a bin, adding the
+/// `Solution` struct LeetCode normally provides and an empty `main` when they are missing.
+pub fn as_solution(contents: &str) -> String {
+    let mut out = String::new();
+    if contents.contains("impl Solution")
+        && !Regex::new(r"struct\s+Solution\b")
+            .unwrap()
+            .is_match(contents)
+    {
+        out.push_str("struct Solution;\n\n");
+    }
+    out.push_str(contents.trim_end());
+    out.push('\n');
+    if !Regex::new(r"fn\s+main\s*\(").unwrap().is_match(contents) {
+        out.push_str("\nfn main() {}\n");
+    }
+    out
+}
+
+/// The `.rs` files under `dir`, leaving out build output and hidden directories.
+fn solution_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut out = Vec::new();
+    for path in fs::read_dir(dir)?.flatten().map(|a| a.path()) {
+        let name = path
+            .file_name()
+            .map(|a| a.to_string_lossy().to_string())
+            .unwrap_or_default();
+        if path.is_dir() {
+            if name != "target" && !name.starts_with('.') {
+                out.extend(solution_files(&path)?);
+            }
+        } else if path.extension().is_some_and(|a| a == "rs") && name != "build.rs" {
+            out.push(path);
+        }
+    }
+    out.sort();
+    Ok(out)
+}
+
+/// A solution to import, with the files of the modules it declares when it is split over several.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Source {
+    pub path: PathBuf,
+    pub contents: String,
+    /// The other files in its directory, relative to it, which move along with it
+    pub modules: Vec<(PathBuf, String)>,
+}
+
+/// Groups `files` into solutions. A `mod.rs`, `main.rs` or `lib.rs` that has `impl Solution` and
+/// declares modules takes every other file under its directory along with it, so a helper next to
+/// it isn't taken for a problem of its own and the solution still builds once moved.
+pub fn group_modules(files: Vec<(PathBuf, String)>) -> Vec<Source> {
+    let declares = Regex::new(r"(?m)^\s*(?:pub(?:\([^)]*\))?\s+)?mod\s+\w+\s*;").unwrap();
+    let roots = files
+        .iter()
+        .filter(|(path, contents)| {
+            path.file_stem()
+                .and_then(|a| a.to_str())
+                .is_some_and(|a| GENERIC_NAMES.contains(&a))
+                && contents.contains("impl Solution")
+                && declares.is_match(contents)
+        })
+        .filter_map(|(path, _)| Some((path.clone(), path.parent()?.to_path_buf())))
+        .collect::<Vec<_>>();
+    let root_of = |path: &Path| {
+        roots
+            .iter()
+            .filter(|(root, dir)| root != path && path.starts_with(dir))
+            // the closest one, should they be nested
+            .max_by_key(|(_, dir)| dir.components().count())
+    };
+    let mut out = Vec::new();
+    let mut modules = BTreeMap::<PathBuf, Vec<(PathBuf, String)>>::new();
+    for (path, contents) in files {
+        match root_of(&path) {
+            Some((root, dir)) => modules.entry(root.clone()).or_default().push((
+                path.strip_prefix(dir).unwrap_or(&path).to_path_buf(),
+                contents,
+            )),
+            None => out.push(Source {
+                path,
+                contents,
+                modules: Vec::new(),
+            }),
+        }
+    }
+    for source in out.iter_mut() {
+        source.modules = modules.remove(&source.path).unwrap_or_default();
+    }
+    out
+}
+
+/// Finds the problem `identity` refers to in the catalog.
+pub fn resolve<'a>(identity: &Identity, catalog: &'a Catalog) -> Result<&'a ListedProblem> {
+    match identity {
+        Identity::Number(num) => match catalog.by_number(*num) {
+            Some(a) => Ok(a),
+            None => Err(Error::not_found(format!("Problem {num} is not in the catalog")).into()),
+        },
+        Identity::Slug(slug) => match catalog.by_slug(slug) {
+            Some(a) => Ok(a),
+            None => find_problem(slug, &catalog.problems.iter().collect::<Vec<_>>()),
+        },
+        Identity::Titled { num, title } => match catalog.by_number(*num) {
+            Some(a) if same_title(title, &a.title) => Ok(a),
+            Some(a) => Err(Error::not_found(format!(
+                "Its header says `{num}. {title}` but Problem {num} is `{}`",
+                a.title
+            ))
+            .into()),
+            None => Err(Error::not_found(format!("Problem {num} is not in the catalog")).into()),
+        },
+    }
+}
+
+/// Writes `source` as the solution of `prob` in the repo, its modules next to `main.rs`, with the
+/// README and TAGS `lc new` would give it.
+fn import_file(repo: &Repo, prob: &ListedProblem, source: &Source) -> Result<()> {
+    let num = prob.frontend_id;
+    if has_bin(repo, num)? {
+        return Err(Error::AlreadyExists(num).into());
+    }
+    let dir = repo.solution_dir(num);
+    repo.create_dir_all(&dir)?;
+    repo.write(&repo.main_rs(num), &as_solution(&source.contents))?;
+    for (path, contents) in &source.modules {
+        let path = dir.join(path);
+        if let Some(parent) = path.parent() {
+            repo.create_dir_all(parent)?;
+        }
+        repo.write(&path, contents)?;
+    }
+    repo.write(
+        &repo.problem_readme(num),
+        &format!(
+            "# {}. {}\n\n[Here]({}) is the link to the problem.",
+            num,
+            prob.title,
+            prob.link()
+        ),
+    )?;
+    repo.write(&repo.tags(num), "")?;
+    Meta {
+        title_slug: Some(prob.title_slug.clone()),
+        question_id: Some(prob.question_id),
+        difficulty: prob.difficulty,
+        created: Some(Utc::now()),
+        ..Default::default()
+    }
+    .write(repo, num)?;
+    add_bin(repo, num)
+}
+
+/// Moves every solution under `dir` that can be matched to a problem in the catalog into the
+/// repo, reporting the files that couldn't be.
+pub fn dir(repo: &Repo, dir: &Path, dry_run: bool) -> Result<()> {
+    let catalog = Catalog::load(repo)?;
+    let mut tracked = tracked_problems(repo)?;
+    let mut unmatched = Vec::new();
+    let mut imported = 0;
+    let mut files = Vec::new();
+    // the folder is outside the repo, so it is read from disk rather than through `repo`
+    for path in solution_files(dir)? {
+        files.push((path.clone(), fs::read_to_string(&path)?));
+    }
+    for source in group_modules(files) {
+        let path = &source.path;
+        let mut shown = path.strip_prefix(dir).unwrap_or(path).display().to_string();
+        if !source.modules.is_empty() {
+            shown.push_str(&format!(" and {} module file(s)", source.modules.len()));
+        }
+        let prob = match identify(path, &source.contents) {
+            Some(identity) => resolve(&identity, &catalog),
+            None => Err(
+                Error::not_found("Nothing in its name or header says which problem it is").into(),
+            ),
+        };
+        let prob = match prob {
+            Ok(a) if tracked.contains(&a.frontend_id) => {
+                unmatched.push((
+                    shown,
+                    format!("Problem {} is already tracked", a.frontend_id),
+                ));
+                continue;
+            }
+            Ok(a) => a,
+            Err(e) => {
+                unmatched.push((shown, e.to_string()));
+                continue;
+            }
+        };
+        if dry_run {
+            println!(
+                "{shown} would be Problem {}. {}",
+                prob.frontend_id, prob.title
+            );
+        } else {
+            if let Err(e) = import_file(repo, prob, &source) {
+                unmatched.push((shown, e.to_string()));
+                continue;
+            }
+            fs::remove_file(path)?;
+            for (module, _) in &source.modules {
+                fs::remove_file(path.parent().unwrap_or(dir).join(module))?;
+            }
+            println!(
+                "{shown} is now Problem {}. {}",
+                prob.frontend_id, prob.title
+            );
+        }
+        tracked.push(prob.frontend_id);
+        imported += 1;
+    }
+
+    if !unmatched.is_empty() {
+        println!("Could not import:");
+        for (path, reason) in &unmatched {
+            println!("  {path}: {reason}");
+        }
+    }
+    let done = if dry_run { "Would import" } else { "Imported" };
+    println!("{done}: {imported}, not imported: {}", unmatched.len());
+    Ok(())
+}
+
+pub async fn import_subcommands(repo: &Repo, cmd: &ImportCommand) -> Result<()> {
+    match cmd {
+        ImportCommand::Remote => remote(repo).await,
+        ImportCommand::Dir { path, dry_run } => dir(repo, path, *dry_run),
+    }
+}
@@ -1 +1,187 @@
+// status and other data about a tracked problem
+use anyhow::Result;
+use chrono::{DateTime, NaiveDate, Utc};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumIter, EnumString};
 
+use super::common::tracked_problems;
+use super::error::Error;
+use super::repo::Repo;
+use super::review::Review;
+use super::tag::Tags;
+
+/// Where a problem is at. Every tracked problem starts out as `Attempted`.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    Display,
+    EnumString,
+    EnumIter,
+)]
+#[strum(ascii_case_insensitive)]
+pub enum Status {
+    #[default]
+    Attempted,
+    Hidden,
+    Finished,
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+    Display,
+    EnumString,
+    EnumIter,
+)]
+#[strum(ascii_case_insensitive)]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+}
+
+/// Data about a problem that isn't part of its solution, stored as `META.json` next to its
+/// README. Problems created before this existed have no file and use the defaults.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct Meta {
+    pub title_slug: Option<String>,
+    // the backend id used when submitting, can differ from the number shown on the site
+    pub question_id: Option<usize>,
+    pub difficulty: Option<Difficulty>,
+    pub status: Status,
+    pub created: Option<DateTime<Utc>>,
+    /// When the problem was first finished, kept when it is hidden or attempted again
+    pub finished: Option<DateTime<Utc>>,
+    /// The spaced repetition schedule, once the problem has been reviewed
+    pub review: Option<Review>,
+    /// The days this problem was started as the daily challenge
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub dailies: Vec<NaiveDate>,
+    /// The verdict of the last time the solution was submitted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_submission: Option<LastSubmission>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LastSubmission {
+    /// e.g. `Accepted` or `Wrong Answer`
+    pub status: String,
+    pub runtime: Option<String>,
+    pub submitted: DateTime<Utc>,
+}
+
+impl Meta {
+    /// Reads the metadata of problem `num`, using the defaults when it has none.
+    pub fn read(repo: &Repo, num: usize) -> Result<Meta> {
+        match repo.read_optional(&repo.meta(num))? {
+            Some(a) => Ok(serde_json::from_str(&a)?),
+            None => Ok(Meta::default()),
+        }
+    }
+
+    pub fn write(&self, repo: &Repo, num: usize) -> Result<()> {
+        let mut buf = serde_json::to_string_pretty(self)?;
+        buf.push('\n');
+        repo.write(&repo.meta(num), &buf)?;
+        Ok(())
+    }
+}
+
+/// Everything known about a tracked problem.
+#[derive(Debug, Clone)]
+pub struct ProblemInfo {
+    pub number: usize,
+    pub title: String,
+    pub link: String,
+    pub meta: Meta,
+    pub tags: Tags,
+}
+
+/// Reads the README, TAGS and META.json of problem `num`.
+pub fn load(repo: &Repo, num: usize) -> Result<ProblemInfo> {
+    let readme = repo
+        .read_optional(&repo.problem_readme(num))
+        .ok()
+        .flatten()
+        .unwrap_or_default();
+    // the README starts with `# <num>. <title>` and then links to the problem
+    let title = Regex::new(r"(?m)^#\s*\d+\.\s*(?<title>.+)$")
+        .unwrap()
+        .captures(&readme)
+        .map(|caps| caps["title"].trim().to_owned())
+        .unwrap_or_default();
+    let link = Regex::new(r"\[Here\]\((?<link>[^)]+)\)")
+        .unwrap()
+        .captures(&readme)
+        .map(|caps| caps["link"].to_owned())
+        .unwrap_or_default();
+    Ok(ProblemInfo {
+        number: num,
+        title,
+        link,
+        meta: Meta::read(repo, num)?,
+        tags: Tags::read(repo, num).unwrap_or_default(),
+    })
+}
+
+/// Loads every tracked problem, in order of problem number.
+pub fn load_all(repo: &Repo) -> Result<Vec<ProblemInfo>> {
+    tracked_problems(repo)?
+        .into_iter()
+        .map(|a| load(repo, a))
+        .collect()
+}
+
+/// Moves problem `num` to `status`, recording when it was first finished.
+pub fn set_status(repo: &Repo, num: usize, status: Status) -> Result<()> {
+    if !tracked_problems(repo)?.contains(&num) {
+        return Err(Error::NotTracked(num).into());
+    }
+    let mut meta = Meta::read(repo, num)?;
+    meta.status = status;
+    // stats, reviews and exports all count from the first finish, so it is never moved or cleared
+    if status == Status::Finished && meta.finished.is_none() {
+        meta.finished = Some(Utc::now());
+    }
+    meta.write(repo, num)
+}
+
+pub fn run(repo: &Repo, num: &usize) -> Result<()> {
+    let info = load(repo, *num)?;
+    println!("{}. {}", info.number, info.title);
+    println!("Link: {}", info.link);
+    println!(
+        "Difficulty: {}",
+        info.meta
+            .difficulty
+            .map(|a| a.to_string())
+            .unwrap_or("Unknown".to_owned())
+    );
+    println!("Status: {}", info.meta.status);
+    if let Some(created) = info.meta.created {
+        println!("Created: {}", created.format("%Y-%m-%d %H:%M"));
+    }
+    if let Some(finished) = info.meta.finished {
+        println!("Finished: {}", finished.format("%Y-%m-%d %H:%M"));
+    }
+    println!(
+        "Tags: {}",
+        info.tags.iter().cloned().collect::<Vec<_>>().join(", ")
+    );
+    Ok(())
+}
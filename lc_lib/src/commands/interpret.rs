@@ -0,0 +1,69 @@
+// running a solution against the example cases on LeetCode without submitting it
+use anyhow::Result;
+
+use super::cache::ensure_online;
+use super::common::{Stage, GQL_ENDPOINT, LEETCODE_HOST};
+use super::error::Error;
+use super::new::query_endpoint;
+use super::repo::Repo;
+use super::request::send_json;
+use super::submit::{prepare, wait_for_judgement, Judgement};
+
+/// Gets the example cases shown in the problem statement, one string per case.
+pub async fn example_testcases(client: &reqwest::Client, title_slug: &str) -> Result<Vec<String>> {
+    let query = serde_json::json!({"query":"\n    query consolePanelConfig($titleSlug: String!) {\n  question(titleSlug: $titleSlug) {\n    exampleTestcaseList\n  }\n}\n    ","variables":{"titleSlug":title_slug},"operationName":"consolePanelConfig"});
+    let data = query_endpoint(&GQL_ENDPOINT.to_string(), &query, client).await?;
+    match data["data"]["question"]["exampleTestcaseList"].as_array() {
+        Some(a) => Ok(a
+            .iter()
+            .filter_map(|a| a.as_str().map(str::to_owned))
+            .collect()),
+        None => Err(Error::parse("Could not get the exampleTestcaseList from JSON").into()),
+    }
+}
+
+/// Runs the solution of problem `num` against the example cases and waits for the result.
+pub async fn interpret(repo: &Repo, num: usize) -> Result<Judgement> {
+    interpret_with_progress(repo, num, |_| {}).await
+}
+
+/// Like [`interpret`], calling `progress` while the run waits to be judged.
+pub async fn interpret_with_progress(
+    repo: &Repo,
+    num: usize,
+    progress: impl Fn(Stage),
+) -> Result<Judgement> {
+    ensure_online("running the examples on LeetCode")?;
+    let (client, sol) = prepare(repo, num).await?;
+    let cases = example_testcases(&client, &sol.title_slug).await?;
+    let resp = send_json(
+        client
+            .post(format!(
+                "https://{LEETCODE_HOST}/problems/{}/interpret_solution/",
+                sol.title_slug
+            ))
+            .json(&serde_json::json!({
+                "lang": "rust",
+                "question_id": sol.question_id.to_string(),
+                "typed_code": sol.code,
+                "data_input": cases.join("\n"),
+            })),
+        true,
+    )
+    .await?;
+    let id = match resp["interpret_id"].as_str() {
+        Some(a) => a.to_owned(),
+        None => {
+            return Err(Error::network(format!("LeetCode did not accept the run: {resp}")).into())
+        }
+    };
+    let mut judgement = wait_for_judgement(&client, &id, true, progress).await?;
+    judgement.input = cases;
+    Ok(judgement)
+}
+
+pub async fn run(repo: &Repo, num: &usize) -> Result<()> {
+    let judgement = interpret(repo, *num).await?;
+    println!("{judgement}");
+    Ok(())
+}
@@ -0,0 +1,126 @@
+// parsing the different shapes of links to LeetCode problems
+use anyhow::Result;
+use reqwest::Url;
+use std::str::FromStr;
+
+use super::common::LEETCODE_HOST;
+use super::error::Error;
+
+/// The problem a LeetCode link points at.
+///
+/// Handles `/problems/<slug>` with or without a trailing slash, the tabs under a problem like
+/// `/problems/<slug>/description/`, contest problems at `/contest/<contest>/problems/<slug>/`,
+/// and submissions at `/problems/<slug>/submissions/<id>/`. Query strings and fragments are
+/// ignored.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProblemRef {
+    pub slug: String,
+    pub contest: Option<String>,
+    pub submission: Option<u64>,
+}
+
+impl ProblemRef {
+    pub fn parse(link: &str) -> Result<ProblemRef> {
+        let link = link.trim();
+        // links copied without the scheme, like `leetcode.com/problems/two-sum`
+        let full = match link.contains("://") {
+            true => link.to_owned(),
+            false => format!("https://{link}"),
+        };
+        let url = match Url::parse(&full) {
+            Ok(a) => a,
+            Err(e) => return Err(Error::parse(format!("`{link}` is not a link: {e}")).into()),
+        };
+        match url.host_str() {
+            Some(a) if a.eq_ignore_ascii_case(LEETCODE_HOST) => {}
+            Some(a) if a.eq_ignore_ascii_case(&format!("www.{LEETCODE_HOST}")) => {}
+            Some(a) => {
+                return Err(Error::parse(format!(
+                    "`{a}` is not {LEETCODE_HOST}, the link must be to a problem on it"
+                ))
+                .into())
+            }
+            None => {
+                return Err(Error::parse(format!(
+                    "`{link}` has no host, the link must be to a problem on {LEETCODE_HOST}"
+                ))
+                .into())
+            }
+        }
+
+        let segments = url
+            .path_segments()
+            .map(|a| a.filter(|a| !a.is_empty()).collect::<Vec<_>>())
+            .unwrap_or_default();
+        let (contest, rest) =
+            match segments.as_slice() {
+                ["problems", rest @ ..] => (None, rest),
+                ["contest", contest, "problems", rest @ ..] => (Some(contest.to_string()), rest),
+                ["contest", contest, ..] => {
+                    return Err(Error::parse(format!(
+                        "The link is to contest `{contest}` rather than one of its problems"
+                    ))
+                    .into())
+                }
+                ["submissions", ..] => return Err(Error::parse(
+                    "Submission links don't say which problem they are for, use the link under \
+                     the problem's Submissions tab instead",
+                )
+                .into()),
+                _ => {
+                    return Err(Error::parse(format!(
+                        "`{}` is not the path of a problem, expected `/problems/<slug>/`",
+                        url.path()
+                    ))
+                    .into())
+                }
+            };
+
+        let Some((slug, tab)) = rest.split_first() else {
+            return Err(
+                Error::parse(format!("`{}` is missing the problem's slug", url.path())).into(),
+            );
+        };
+        if !slug
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+        {
+            return Err(Error::parse(format!(
+                "`{slug}` is not a problem slug, those are lowercase words joined by `-`"
+            ))
+            .into());
+        }
+        let submission = match tab {
+            ["submissions", id, ..] => match id.parse::<u64>() {
+                Ok(a) => Some(a),
+                Err(_) => return Err(Error::parse(format!("`{id}` is not a submission id")).into()),
+            },
+            _ => None,
+        };
+        Ok(ProblemRef {
+            slug: slug.to_string(),
+            contest,
+            submission,
+        })
+    }
+
+    /// The canonical link to the problem, e.g. `https://leetcode.com/problems/two-sum/`.
+    pub fn link(&self) -> Url {
+        Url::parse(&format!("https://{LEETCODE_HOST}/problems/{}/", self.slug))
+            .expect("slugs only hold characters that are valid in a path")
+    }
+}
+
+impl FromStr for ProblemRef {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        ProblemRef::parse(s)
+    }
+}
+
+impl std::fmt::Display for ProblemRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.link())
+    }
+}
@@ -0,0 +1,140 @@
+// running the tests that live alongside a solution in the repo
+use anyhow::Result;
+use std::{
+    io::{BufRead, BufReader, Read},
+    path::Path,
+    process::{Command, Stdio},
+    time::SystemTime,
+};
+
+use super::common::Stage;
+use super::repo::Repo;
+
+/// The outcome of running `cargo test` for a single problem.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TestSummary {
+    pub passed: usize,
+    pub failed: usize,
+    pub ignored: usize,
+    /// Names of the tests that failed
+    pub failures: Vec<String>,
+    /// The first compiler error when the solution didn't build
+    pub build_error: Option<String>,
+    /// Everything cargo printed, for showing the details of a failure
+    pub output: String,
+}
+
+impl TestSummary {
+    pub fn success(&self) -> bool {
+        self.build_error.is_none() && self.failed == 0
+    }
+
+    /// Parses the combined stdout and stderr of `cargo test`.
+    pub fn parse(output: &str) -> TestSummary {
+        let mut out = TestSummary {
+            output: output.to_owned(),
+            ..Default::default()
+        };
+        let mut saw_result = false;
+        for line in output.lines() {
+            if let Some(rest) = line.strip_prefix("test result: ") {
+                saw_result = true;
+                for part in rest.split(';') {
+                    let mut words = part.split_whitespace().rev();
+                    let (Some(kind), Some(count)) = (words.next(), words.next()) else {
+                        continue;
+                    };
+                    let count = count.parse::<usize>().unwrap_or(0);
+                    match kind {
+                        "passed" => out.passed += count,
+                        "failed" => out.failed += count,
+                        "ignored" => out.ignored += count,
+                        _ => {}
+                    }
+                }
+            } else if let Some(name) = line
+                .strip_prefix("test ")
+                .and_then(|a| a.strip_suffix(" ... FAILED"))
+            {
+                out.failures.push(name.to_owned());
+            } else if out.build_error.is_none() && line.starts_with("error") {
+                out.build_error = Some(line.to_owned());
+            }
+        }
+        // cargo also prints `error: test failed` when tests fail, that isn't a build error
+        if saw_result {
+            out.build_error = None;
+        }
+        out
+    }
+
+    /// A single line description like `3 passed, 1 failed (two_sum_works)`.
+    pub fn compact(&self) -> String {
+        if let Some(e) = &self.build_error {
+            return format!("build failed: {e}");
+        }
+        let mut out = format!("{} passed, {} failed", self.passed, self.failed);
+        if self.ignored > 0 {
+            out.push_str(&format!(", {} ignored", self.ignored));
+        }
+        if !self.failures.is_empty() {
+            out.push_str(&format!(" ({})", self.failures.join(", ")));
+        }
+        out
+    }
+}
+
+/// Runs the tests of problem `num` with `cargo test --bin <num>` inside the repo.
+pub fn run_tests(repo: &Repo, num: usize) -> Result<TestSummary> {
+    run_tests_with_progress(repo, num, |_| {})
+}
+
+/// Like [`run_tests`], calling `progress` as cargo moves from building the solution to running it.
+pub fn run_tests_with_progress(
+    repo: &Repo,
+    num: usize,
+    progress: impl Fn(Stage),
+) -> Result<TestSummary> {
+    let mut child = Command::new("cargo")
+        .args(["test", "--color", "never", "--bin"])
+        .arg(num.to_string())
+        .current_dir(repo.root())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    progress(Stage::Queued);
+
+    // stdout is drained on its own thread so neither pipe can fill up while the other is read
+    let mut stdout = child.stdout.take().expect("stdout is piped");
+    let stdout = std::thread::spawn(move || {
+        let mut out = String::new();
+        let _ = stdout.read_to_string(&mut out);
+        out
+    });
+    let mut text = String::new();
+    let stderr = child.stderr.take().expect("stderr is piped");
+    for line in BufReader::new(stderr).lines() {
+        let line = line?;
+        match line.split_whitespace().next() {
+            Some("Compiling") => progress(Stage::Compiling),
+            Some("Running") => progress(Stage::Running),
+            _ => {}
+        }
+        text.push_str(&line);
+        text.push('\n');
+    }
+    child.wait()?;
+    text.push_str(&stdout.join().unwrap_or_default());
+    Ok(TestSummary::parse(&text))
+}
+
+/// The most recent modification time of any file in `dir`.
+pub fn latest_change(repo: &Repo, dir: &Path) -> Result<SystemTime> {
+    let mut out = SystemTime::UNIX_EPOCH;
+    for path in repo.list(dir)? {
+        if let Some(modified) = repo.modified(&path) {
+            out = out.max(modified);
+        }
+    }
+    Ok(out)
+}
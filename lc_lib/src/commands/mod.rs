@@ -2,9 +2,26 @@ pub mod common;
 pub mod finish;
 pub mod hide;
 pub mod info;
+pub mod local;
 pub mod new;
 pub mod search;
 pub mod submit;
 pub mod tag;
 pub mod interpret;
 pub mod edit;
+pub mod stats;
+pub mod review;
+pub mod daily;
+pub mod browse;
+pub mod catalog;
+pub mod link;
+pub mod import;
+pub mod export;
+pub mod readme;
+pub mod git;
+pub mod cache;
+pub mod request;
+pub mod error;
+pub mod fs;
+pub mod repo;
+pub mod store;
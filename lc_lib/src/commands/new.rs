@@ -1,15 +1,46 @@
-use std::{io::Write, str::FromStr};
+use std::{path::Path, sync::Arc};
+use tokio::{sync::Semaphore, task::JoinSet};
 
-use super::common::{get_lc_dir, GQL_ENDPOINT};
+use super::browse::{fetch_page, BrowseFilter, ListedProblem};
+use super::cache::{self, CacheKey, CacheMode};
+use super::catalog::Catalog;
+use super::common::{suggest, tracked_problems, GQL_ENDPOINT, LEETCODE_HOST};
+use super::error::Error;
+use super::info::{Difficulty, Meta};
+use super::link::ProblemRef;
+use super::repo::Repo;
+use super::request::{graphql_error, send_json};
 use anyhow::Result;
 use regex::Regex;
 use reqwest::Url;
 use serde::{Deserialize, Serialize};
 
-const LEETCODE_HOST: &str = "leetcode.com";
+/// How many search results are looked through when resolving a problem without a catalog.
+const SEARCH_RESULTS: usize = 20;
+/// How many problems a batch `lc new` fetches from LeetCode at the same time.
+const NEW_CONCURRENCY: usize = 4;
 
-pub async fn run(link: &String) -> Result<()> {
-    let link = sanitize_lc_link(link)?;
+pub async fn run(repo: &Repo, problem: &str) -> Result<()> {
+    let problem_data = fetch_problem(repo, problem).await?;
+
+    // create the directory things inside the repo from problem data
+    match create_entry(repo, problem_data) {
+        Ok(()) => {
+            // return a good note
+            println!("Successfully created the problem!");
+            Ok(())
+        }
+        Err(e) => {
+            // return an error of what the issue was
+            println!("Failed to create the directory: {e}");
+            Err(e)
+        }
+    }
+}
+
+/// Gets everything needed to create a problem from LeetCode, without touching the repo.
+pub async fn fetch_problem(repo: &Repo, problem: &str) -> Result<Problem> {
+    let link = resolve_link(repo, problem).await?;
     let client = generate_request_client(&link)?;
 
     // this goes inside of the query that is sent
@@ -25,7 +56,7 @@ pub async fn run(link: &String) -> Result<()> {
     // code snippets
     // and a capability to add more
 
-    let query = serde_json::json!({"query":"\n    query questionEditorData($titleSlug: String!) {\n  question(titleSlug: $titleSlug) {\n    questionId\n    questionFrontendId\n    title\n    codeSnippets {\n      lang\n      langSlug\n      code\n    }\n    envInfo\n    enableRunCode\n    hasFrontendPreview\n    frontendPreviews\n  }\n}\n    ","variables":{"titleSlug":title_slug},"operationName":"questionEditorData"});
+    let query = serde_json::json!({"query":"\n    query questionEditorData($titleSlug: String!) {\n  question(titleSlug: $titleSlug) {\n    questionId\n    questionFrontendId\n    title\n    titleSlug\n    difficulty\n    isPaidOnly\n    content\n    codeSnippets {\n      lang\n      langSlug\n      code\n    }\n    envInfo\n    enableRunCode\n    hasFrontendPreview\n    frontendPreviews\n  }\n}\n    ","variables":{"titleSlug":title_slug},"operationName":"questionEditorData"});
     let data = query_endpoint(&GQL_ENDPOINT.to_string(), &query, &client).await?;
 
     // parse the data into a single struct that can be converted to json and stored in the
@@ -33,20 +64,113 @@ pub async fn run(link: &String) -> Result<()> {
     let mut problem_data = parse_from_json_to_problem(data)?;
     // link is not set yet
     problem_data.link = link.to_string();
+    Ok(problem_data)
+}
 
-    // create the directory things inside the repo from problem data
-    match create_entry(problem_data) {
-        Ok(()) => {
-            // return a good note
-            println!("Successfully created the problem!");
-            Ok(())
+/// What happened to each problem given to a batch `lc new`.
+#[derive(Debug, Default)]
+pub struct BatchSummary {
+    pub created: Vec<usize>,
+    /// Problems that were already tracked, by what was given for them
+    pub skipped: Vec<String>,
+    pub failed: Vec<(String, anyhow::Error)>,
+}
+
+/// Creates every problem in `problems`. Up to `NEW_CONCURRENCY` are fetched from LeetCode at once
+/// but they are written to the repo one at a time, since they all append to the same Cargo.toml.
+pub async fn run_batch(repo: &Repo, problems: Vec<String>) -> Result<BatchSummary> {
+    let tracked = tracked_problems(repo)?;
+    let permits = Arc::new(Semaphore::new(NEW_CONCURRENCY));
+    let mut fetches = JoinSet::new();
+    let mut out = BatchSummary::default();
+    for problem in problems {
+        if problem.parse::<usize>().is_ok_and(|a| tracked.contains(&a)) {
+            out.skipped.push(problem);
+            continue;
         }
-        Err(e) => {
-            // return an error of what the issue was
-            println!("Failed to create the directory: {e}");
-            Err(e)
+        let permits = permits.clone();
+        let repo = repo.clone();
+        fetches.spawn(async move {
+            let _permit = permits.acquire_owned().await;
+            // fetched in a task of its own so a panic is still reported against `problem`
+            let given = problem.clone();
+            let fetch = tokio::spawn(async move { fetch_problem(&repo, &given).await });
+            (problem, fetch.await.unwrap_or_else(|e| Err(e.into())))
+        });
+    }
+
+    while let Some(joined) = fetches.join_next().await {
+        let (problem, res) = joined?;
+        let prob = match res {
+            Ok(a) => a,
+            Err(e) => {
+                println!("Failed to fetch {problem}: {e}");
+                out.failed.push((problem, e));
+                continue;
+            }
+        };
+        let number = prob.number;
+        if tracked_problems(repo)?.contains(&number) {
+            println!("Problem {number} is already tracked");
+            out.skipped.push(problem);
+            continue;
+        }
+        match create_entry(repo, prob) {
+            Ok(()) => {
+                println!("Created Problem {number}");
+                out.created.push(number);
+            }
+            Err(e) => {
+                println!("Failed to create Problem {number}: {e}");
+                out.failed.push((problem, e));
+            }
         }
     }
+    out.created.sort_unstable();
+    Ok(out)
+}
+
+/// The problems listed in a file for a batch `lc new`, one per line, skipping blank lines and
+/// `#` comments.
+pub fn read_problem_list(repo: &Repo, path: &Path) -> Result<Vec<String>> {
+    let contents = repo.read(path)?;
+    Ok(contents
+        .lines()
+        .map(|line| line.split_once('#').map_or(line, |(a, _)| a).trim())
+        .filter(|a| !a.is_empty())
+        .map(str::to_owned)
+        .collect())
+}
+
+pub async fn run_many(repo: &Repo, mut problems: Vec<String>, file: Option<&Path>) -> Result<()> {
+    if let Some(path) = file {
+        problems.extend(read_problem_list(repo, path)?);
+    }
+    match problems.as_slice() {
+        [] => return Err(anyhow::Error::msg("No problems were given")),
+        [problem] => return run(repo, problem).await,
+        _ => {}
+    }
+    let summary = run_batch(repo, problems).await?;
+    println!(
+        "Created: {}, skipped: {}, failed: {}",
+        summary.created.len(),
+        summary.skipped.len(),
+        summary.failed.len()
+    );
+    if summary.failed.is_empty() {
+        Ok(())
+    } else {
+        Err(anyhow::Error::msg(format!(
+            "Failed to create: {}",
+            summary
+                .failed
+                .iter()
+                .map(|a| a.0.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )))
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -57,51 +181,110 @@ pub struct Problem {
     snippet: String,
     title: String,
     link: String,
+    title_slug: String,
+    difficulty: Option<Difficulty>,
+    // problem statement as Markdown
+    statement: String,
 }
 
-pub fn sanitize_lc_link(link: &String) -> Result<Url> {
-    let link = link.parse::<Url>()?;
-    match link.host_str() {
-        Some(a) if !a.eq_ignore_ascii_case(LEETCODE_HOST) => {
-            return Err(anyhow::Error::msg(format!(
-                "Host in link is incorrect: {a}"
-            )))
-        }
-        None => {
-            return Err(anyhow::Error::msg(format!(
-                "Incorrect or unexpected link: {link}"
-            )))
-        }
-        // nop if the host is good
-        _ => {}
+impl Problem {
+    pub fn number(&self) -> usize {
+        self.number
+    }
+
+    /// Replaces LeetCode's starting code with a solution, e.g. one submitted before the problem
+    /// was tracked.
+    pub fn set_solution(&mut self, code: String) {
+        self.snippet = code;
+    }
+}
+
+/// Turns what was given to `lc new` into a link to the problem. Besides links this accepts a
+/// problem number, a title slug or a title, which are looked up in the catalog when there is one
+/// and searched for on LeetCode otherwise.
+pub async fn resolve_link(repo: &Repo, problem: &str) -> Result<Url> {
+    let problem = problem.trim();
+    // slugs and titles never have a `/` in them
+    if problem.contains('/') {
+        return sanitize_lc_link(problem);
+    }
+
+    let from_catalog = match Catalog::read(repo)? {
+        Some(catalog) => Some(
+            find_problem(problem, &catalog.problems.iter().collect::<Vec<_>>())
+                .map(|a| a.title_slug.clone()),
+        ),
+        None => None,
+    };
+    let slug = match from_catalog {
+        Some(Ok(slug)) => slug,
+        // the catalog can be out of date, so LeetCode gets a say before giving up
+        Some(Err(e)) => match search(problem).await {
+            Ok(Ok(slug)) => slug,
+            Ok(Err(_)) | Err(_) => return Err(e),
+        },
+        None => search(problem).await??,
+    };
+    Ok(ProblemRef {
+        slug,
+        contest: None,
+        submission: None,
+    }
+    .link())
+}
+
+/// Searches LeetCode for `problem`. The outer error is for the request, the inner one for when
+/// nothing in the results matches.
+async fn search(problem: &str) -> Result<Result<String>> {
+    let client = generate_request_client(&Url::parse(&format!(
+        "https://{LEETCODE_HOST}/problemset/"
+    ))?)?;
+    let filter = BrowseFilter {
+        keyword: Some(problem.replace('-', " ")),
+        ..Default::default()
     };
+    let (_, results) = fetch_page(&client, &filter, 0, SEARCH_RESULTS).await?;
+    Ok(find_problem(problem, &results.iter().collect::<Vec<_>>()).map(|a| a.title_slug.clone()))
+}
 
-    // check path next
-    let re = Regex::new(r"^(/[^/]+/[^/]+/)").unwrap();
-    let path = match re.captures(link.path()) {
-        Some(caps) => caps[0].to_owned(),
-        None => {
-            return Err(anyhow::Error::msg(format!(
-                "Path to problem was incorrect: {}",
-                link.path()
-            )))
-        }
+/// Finds the problem a number, slug or title refers to among `candidates`, suggesting the closest
+/// title or slug when nothing matches exactly.
+pub fn find_problem<'a>(
+    problem: &str,
+    candidates: &[&'a ListedProblem],
+) -> Result<&'a ListedProblem> {
+    let found = match problem.parse::<usize>() {
+        Ok(num) => candidates.iter().find(|a| a.frontend_id == num),
+        Err(_) => candidates.iter().find(|a| {
+            a.title_slug.eq_ignore_ascii_case(problem) || a.title.eq_ignore_ascii_case(problem)
+        }),
     };
+    if let Some(a) = found {
+        return Ok(a);
+    }
+    if problem.parse::<usize>().is_ok() {
+        return Err(Error::not_found(format!("Problem {problem} was not found")).into());
+    }
+    let names = candidates
+        .iter()
+        .flat_map(|a| [a.title.as_str(), a.title_slug.as_str()]);
+    Err(match suggest(problem, names) {
+        Some(a) => Error::not_found(format!(
+            "No problem is called `{problem}`, did you mean `{a}`?"
+        )),
+        None => Error::not_found(format!("No problem is called `{problem}`")),
+    }
+    .into())
+}
 
-    // return a sanitized version of the link that has the title-slug at the end
-    let url = Url::from_str(format!("https://{}{}", LEETCODE_HOST, path).as_str()).unwrap();
-    Ok(url)
+/// Checks that `link` points at a problem and returns the canonical link to it.
+pub fn sanitize_lc_link(link: &str) -> Result<Url> {
+    Ok(ProblemRef::parse(link)?.link())
 }
 
+/// The title-slug of the problem `link` points at.
 pub fn get_title_slug(link: &Url) -> Result<String> {
-    // retrieves the title-slug from the URL
-    let re = Regex::new(r"/([^/]+)/$").unwrap();
-    match re.captures(link.as_str()) {
-        Some(caps) => Ok(caps[0].trim_matches('/').to_owned()),
-        None => Err(anyhow::Error::msg(format!(
-            "Failed to retrieve the title-slug from the link: {link}"
-        ))),
-    }
+    Ok(ProblemRef::parse(link.as_str())?.slug)
 }
 
 pub async fn query_endpoint(
@@ -121,46 +304,98 @@ pub async fn query_endpoint(
     //         // submissions work
     //         "lang":"rust","question_id":"1","typed_code":"impl Solution {\n    pub fn two_sum(nums: Vec<i32>, target: i32) -> Vec<i32> {\n        use std::collections::HashMap;\n        // hash each number with the index as their value\n        let mut hash: HashMap<i32, i32> = HashMap::new();\n        for (k, v) in nums.iter().zip(0..) {\n            match hash.get(&(target - k)) {\n                Some(i) => return vec![v, *i],\n                None => hash.insert(*k, v),\n            };\n        }\n        vec![]\n    }\n}"
     //     }))
-    let resp: serde_json::Value = client
-        .post(endpoint)
-        .json(query)
-        .send()
-        .await?
-        .json()
-        .await?;
+    let key = CacheKey::of(query);
+    let cached = match cache::mode() {
+        CacheMode::Refresh => None,
+        _ => cache::read(&key),
+    };
+    match (cache::mode(), cached) {
+        (CacheMode::Offline, Some(entry)) => return Ok(entry.response),
+        (CacheMode::Offline, None) => {
+            return Err(Error::network(format!(
+                "Nothing is cached for {} with {}, run without --offline",
+                key.operation, key.variables
+            ))
+            .into())
+        }
+        (_, Some(entry)) if entry.is_fresh(chrono::Utc::now()) => return Ok(entry.response),
+        _ => {}
+    }
+
+    let resp = send_json(client.post(endpoint).json(query), true).await?;
+    // errors next to data are partial answers, but with no data there is nothing to go on
+    let has_data = resp["data"]
+        .as_object()
+        .is_some_and(|a| a.values().any(|a| !a.is_null()));
+    if !has_data {
+        if let Some(e) = graphql_error(&resp) {
+            return Err(e.into());
+        }
+    }
+    // errors aren't worth remembering, and a cache that can't be written only costs a request
+    if cache::ttl(&key.operation).is_some() && resp.get("errors").is_none() {
+        let _ = cache::write(&key, &resp);
+    }
     Ok(resp)
 }
 
 pub fn generate_request_client(sanitized_link: &Url) -> Result<reqwest::Client> {
     use reqwest::header;
     use std::env;
-    let key = "LEETCODE_SESSION";
-    let session = env::var(key)?;
-    let key = "LEETCODE_TOKEN";
-    let token = env::var(key)?;
+    // cached responses don't need to be logged in
+    if cache::mode() == CacheMode::Offline
+        && (env::var("LEETCODE_SESSION").is_err() || env::var("LEETCODE_TOKEN").is_err())
+    {
+        return Ok(reqwest::Client::new());
+    }
+    let var = |key: &str| {
+        env::var(key).map_err(|e| Error::auth(format!("{key} is not set")).caused_by(e))
+    };
+    let session = var("LEETCODE_SESSION")?;
+    let token = var("LEETCODE_TOKEN")?;
 
     let cookies = format!("LEETCODE_SESSION={};csrftoken={}", session, token);
 
     let mut headers = header::HeaderMap::new();
 
     let cookie = header::HeaderValue::from_str(cookies.as_str())?;
-    let referer = header::HeaderValue::from_str(sanitized_link.as_str())?;
     let csrf = header::HeaderValue::from_str(&token)?;
+
+    headers.insert(header::COOKIE, cookie);
+    headers.insert(header::HeaderName::from_static("x-csrftoken"), csrf);
+
+    build_client(sanitized_link, headers)
+}
+
+/// A client for the public parts of LeetCode, e.g. the daily challenge, that works without
+/// `LEETCODE_SESSION` and `LEETCODE_TOKEN`.
+pub fn generate_anonymous_client(sanitized_link: &Url) -> Result<reqwest::Client> {
+    build_client(sanitized_link, reqwest::header::HeaderMap::new())
+}
+
+fn build_client(
+    sanitized_link: &Url,
+    mut headers: reqwest::header::HeaderMap,
+) -> Result<reqwest::Client> {
+    use reqwest::header;
+    let referer = header::HeaderValue::from_str(sanitized_link.as_str())?;
     let content = header::HeaderValue::from_str("application/json")?;
     let accept = header::HeaderValue::from_str("application/json")?;
 
-    headers.insert(header::COOKIE, cookie);
     headers.insert(header::REFERER, referer);
     headers.insert(header::CONTENT_TYPE, content);
     headers.insert(header::ACCEPT, accept);
-    headers.insert(header::HeaderName::from_static("x-csrftoken"), csrf);
 
     reqwest::Client::builder()
         .user_agent("Mozilla/5.0 (X11; Linux x86_64; rv:109.0) Gecko/20100101 Firefox/117")
         .default_headers(headers)
         .cookie_store(true)
         .build()
-        .map_err(|e| anyhow::Error::msg(e.to_string()))
+        .map_err(|e| {
+            Error::network("Could not set up the HTTP client")
+                .caused_by(e)
+                .into()
+        })
 }
 
 pub fn parse_from_json_to_problem(json: serde_json::Value) -> Result<Problem> {
@@ -170,16 +405,15 @@ pub fn parse_from_json_to_problem(json: serde_json::Value) -> Result<Problem> {
     // code snippets
     // and a capability to add more
     let question = &json["data"]["question"];
+    if question.is_null() {
+        return Err(Error::not_found("LeetCode has no such problem").into());
+    }
+    if question["isPaidOnly"].as_bool() == Some(true) && question["codeSnippets"].is_null() {
+        return Err(Error::PremiumOnly.into());
+    }
     let number = match question["questionFrontendId"].as_str() {
-        Some(a) => match a.parse::<usize>() {
-            Ok(a) => a,
-            Err(e) => return Err(e.into()),
-        },
-        None => {
-            return Err(anyhow::Error::msg(
-                "Could not get the questionFrontendId from JSON",
-            ))
-        }
+        Some(a) => a.parse::<usize>()?,
+        None => return Err(Error::parse("Could not get the questionFrontendId from JSON").into()),
     };
     // if backend number != frontend number then backend number = Some(backend)
     // otherwise its None
@@ -189,15 +423,11 @@ pub fn parse_from_json_to_problem(json: serde_json::Value) -> Result<Problem> {
             Ok(a) => Some(a),
             Err(e) => return Err(e.into()),
         },
-        None => return Err(anyhow::Error::msg("Could not get the questionId from JSON")),
+        None => return Err(Error::parse("Could not get the questionId from JSON").into()),
     };
     let len = match question["codeSnippets"].as_array() {
         Some(a) => a.len(),
-        None => {
-            return Err(anyhow::Error::msg(
-                "Could not get the codeSnippets array from JSON",
-            ))
-        }
+        None => return Err(Error::parse("Could not get the codeSnippets array from JSON").into()),
     };
     let mut snippet: String = "".to_string();
     for i in 0..len {
@@ -207,73 +437,96 @@ pub fn parse_from_json_to_problem(json: serde_json::Value) -> Result<Problem> {
                     match question["codeSnippets"][i]["code"].as_str() {
                         Some(a) => snippet = a.to_string(),
                         None => {
-                            return Err(anyhow::Error::msg(
-                                "Could not get the codeSnippet from JSON",
-                            ))
+                            return Err(
+                                Error::parse("Could not get the codeSnippet from JSON").into()
+                            )
                         }
                     }
                 }
             }
-            None => {
-                return Err(anyhow::Error::msg(
-                    "Could not get the codeSnippet from JSON",
-                ))
-            }
+            None => return Err(Error::parse("Could not get the codeSnippet from JSON").into()),
         }
     }
-    if snippet == "".to_string() {
-        return Err(anyhow::Error::msg(
-            "Could not get the codeSnippet from JSON",
-        ));
+    if snippet.is_empty() {
+        return Err(Error::parse("Could not get the codeSnippet from JSON").into());
     }
     let title = match question["title"].as_str() {
         Some(a) => a.to_string(),
-        None => return Err(anyhow::Error::msg("Could not get the title from JSON")),
+        None => return Err(Error::parse("Could not get the title from JSON").into()),
     };
+    let title_slug = question["titleSlug"].as_str().unwrap_or("").to_owned();
+    let difficulty = question["difficulty"]
+        .as_str()
+        .and_then(|a| a.parse::<Difficulty>().ok());
+    let statement = html_to_markdown(question["content"].as_str().unwrap_or(""));
     Ok(Problem {
         number,
         number_backend,
         snippet,
         title,
         link: "".to_owned(),
+        title_slug,
+        difficulty,
+        statement,
     })
 }
 
-pub fn create_entry(prob: Problem) -> Result<()> {
-    let lc_dir = get_lc_dir()?;
+/// Whether the repo's Cargo.toml already has the `[[bin]]` of problem `num`.
+pub fn has_bin(repo: &Repo, num: usize) -> Result<bool> {
+    let cargo = repo.read(&repo.cargo_toml())?;
+    let re = Regex::new(format!(r#"\[\[bin\]\]\nname = \"{}\""#, num).as_str()).unwrap();
+    Ok(re.is_match(&cargo))
+}
 
+/// Adds the `[[bin]]` of problem `num` to the repo's Cargo.toml, failing when it is already there.
+///
+/// A bin whose `main.rs` is missing breaks `cargo build` for the whole repo, so this comes after
+/// every file of the problem has been written.
+pub fn add_bin(repo: &Repo, num: usize) -> Result<()> {
+    if has_bin(repo, num)? {
+        return Err(Error::AlreadyExists(num).into());
+    }
+    // write the bin into Cargo.toml
+    repo.append(
+        &repo.cargo_toml(),
+        &format!(
+            "\n[[bin]]\nname = \"{}\"\npath = \"src/{}/src/main.rs\"\n",
+            num, num
+        ),
+    )?;
+    Ok(())
+}
+
+/// The name of the function `main` should call: the first `pub fn` of `impl Solution`, or its
+/// first `fn` when nothing is public. Comments, attributes and helpers before it are skipped, so
+/// code written by hand works as well as LeetCode's starting code.
+pub fn solution_function(code: &str) -> Option<String> {
+    let body = &code[Regex::new(r"impl\s+Solution\s*\{")
+        .unwrap()
+        .find(code)?
+        .end()..];
+    let body = Regex::new(r"(?s)//[^\n]*|/\*.*?\*/")
+        .unwrap()
+        .replace_all(body, "");
+    [r"\bpub\s+fn\s+(?<func>\w+)", r"\bfn\s+(?<func>\w+)"]
+        .iter()
+        .find_map(|re| Regex::new(re).unwrap().captures(&body))
+        .map(|caps| caps["func"].to_owned())
+}
+
+pub fn create_entry(repo: &Repo, prob: Problem) -> Result<()> {
     // first check if the problem exists already in the Cargo.toml
-    let cargo_path = format!("{}{}", lc_dir, "/Cargo.toml");
-    let cargo = std::fs::read_to_string(cargo_path.clone())?;
-    let re = Regex::new(format!(r#"\[\[bin\]\]\nname = \"{}\""#, prob.number).as_str()).unwrap();
-    if let Some(_a) = re.captures(cargo.as_str()) {
-        return Err(anyhow::Error::msg(format!(
-            "Problem already exists in repo! e: {}",
-            prob.number
-        )));
-    } else {
-        // write the bin into Cargo.toml
-        let mut file = std::fs::OpenOptions::new().append(true).open(cargo_path)?;
-        writeln!(
-            file,
-            "{}",
-            format!(
-                "\n[[bin]]\nname = \"{}\"\npath = \"src/{}/src/main.rs\"",
-                prob.number, prob.number
-            )
-        )?;
+    if has_bin(repo, prob.number)? {
+        return Err(Error::AlreadyExists(prob.number).into());
     }
 
     // then get the function name
-
-    let re = Regex::new(r"impl Solution.*\n\s+pub\sfn\s(?<func>\w+)\s*\(").unwrap();
-    let func = match re.captures(prob.snippet.as_str()) {
-        Some(caps) => caps["func"].to_owned(),
+    let func = match solution_function(&prob.snippet) {
+        Some(a) => a,
         None => {
-            return Err(anyhow::Error::msg(format!(
-                "Could not find function name: {}",
-                prob.snippet
-            )))
+            return Err(
+                Error::parse(format!("Could not find function name: {}", prob.snippet)).into(),
+            )
         }
     };
 
@@ -286,27 +539,179 @@ pub fn create_entry(prob: Problem) -> Result<()> {
     );
 
     let code = format!("{}{}{}", "struct Solution;\n\n", prob.snippet, main);
-    let readme = format!(
+    let mut readme = format!(
         "# {}. {}\n\n[Here]({}) is the link to the problem.",
         prob.number, prob.title, prob.link
     );
+    if !prob.statement.is_empty() {
+        readme.push_str("\n\n");
+        readme.push_str(&prob.statement);
+        readme.push('\n');
+    }
 
     // then make the prob.number directory in src
-    std::fs::create_dir_all(format!("{}{}{}{}", lc_dir, "src/", prob.number, "/src/"))?;
+    repo.create_dir_all(&repo.solution_dir(prob.number))?;
     // then make its write the code in main.rs
-    std::fs::write(
-        format!("{}{}{}{}", lc_dir, "src/", prob.number, "/src/main.rs"),
-        code,
-    )?;
+    repo.write(&repo.main_rs(prob.number), &code)?;
     // README.md
-    std::fs::write(
-        format!("{}{}{}{}", lc_dir, "src/", prob.number, "/README.md"),
-        readme,
-    )?;
+    repo.write(&repo.problem_readme(prob.number), &readme)?;
     // TAGS
-    std::fs::write(
-        format!("{}{}{}{}", lc_dir, "src/", prob.number, "/TAGS"),
-        "",
-    )?;
-    Ok(())
+    repo.write(&repo.tags(prob.number), "")?;
+    // META.json
+    Meta {
+        title_slug: Some(prob.title_slug).filter(|a| !a.is_empty()),
+        question_id: Some(prob.number_backend.unwrap_or(prob.number)),
+        difficulty: prob.difficulty,
+        created: Some(chrono::Utc::now()),
+        ..Default::default()
+    }
+    .write(repo, prob.number)?;
+    // and only then point Cargo.toml at it
+    add_bin(repo, prob.number)
+}
+
+/// Converts the HTML statement LeetCode serves into Markdown for the README.
+///
+/// Only the tags that problem statements actually use are translated. `<sup>` and `<sub>` are
+/// kept as inline HTML since Markdown has no syntax for them, and anything else is dropped.
+pub fn html_to_markdown(html: &str) -> String {
+    let mut out = String::new();
+    let mut rest = html;
+    let mut in_pre = false;
+    // None for an unordered list, otherwise the number of the last item
+    let mut lists: Vec<Option<usize>> = Vec::new();
+    let img_src = Regex::new(r#"src="(?<src>[^"]+)""#).unwrap();
+    while let Some(start) = rest.find('<') {
+        push_html_text(&mut out, &rest[..start], in_pre);
+        let Some(end) = rest[start..].find('>') else {
+            rest = &rest[start..];
+            break;
+        };
+        let tag = &rest[start + 1..start + end];
+        rest = &rest[start + end + 1..];
+
+        let closing = tag.starts_with('/');
+        let name = tag
+            .trim_start_matches('/')
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or("")
+            .to_ascii_lowercase();
+        match (name.as_str(), closing) {
+            ("p" | "div", true) => out.push_str("\n\n"),
+            ("br", _) => out.push('\n'),
+            ("strong" | "b", _) if !in_pre => out.push_str("**"),
+            ("em" | "i", _) if !in_pre => out.push('*'),
+            ("code", _) if !in_pre => out.push('`'),
+            ("pre", false) => {
+                in_pre = true;
+                out.push_str("\n\n```\n");
+            }
+            ("pre", true) => {
+                in_pre = false;
+                while out.ends_with('\n') {
+                    out.pop();
+                }
+                out.push_str("\n```\n\n");
+            }
+            ("ul", false) => lists.push(None),
+            ("ol", false) => lists.push(Some(0)),
+            ("ul" | "ol", true) => {
+                lists.pop();
+                out.push('\n');
+            }
+            ("li", false) => {
+                if !out.ends_with('\n') {
+                    out.push('\n');
+                }
+                out.push_str(&"  ".repeat(lists.len().saturating_sub(1)));
+                match lists.last_mut() {
+                    Some(Some(n)) => {
+                        *n += 1;
+                        out.push_str(&format!("{n}. "));
+                    }
+                    _ => out.push_str("- "),
+                }
+            }
+            ("li", true) => out.push('\n'),
+            ("sup" | "sub", _) => out.push_str(&format!("<{}>", tag.trim())),
+            ("img", false) => {
+                if let Some(caps) = img_src.captures(tag) {
+                    out.push_str(&format!("![]({})", &caps["src"]));
+                }
+            }
+            _ => {}
+        }
+    }
+    push_html_text(&mut out, rest, in_pre);
+
+    let mut cleaned = out
+        .lines()
+        .map(str::trim_end)
+        .collect::<Vec<_>>()
+        .join("\n");
+    while cleaned.contains("\n\n\n") {
+        cleaned = cleaned.replace("\n\n\n", "\n\n");
+    }
+    cleaned.trim().to_owned()
+}
+
+/// Appends text from between HTML tags. Outside of `<pre>` runs of whitespace collapse into a
+/// single space like a browser would show them.
+fn push_html_text(out: &mut String, text: &str, in_pre: bool) {
+    let text = decode_html_entities(text);
+    if in_pre {
+        // the newline right after `<pre>` isn't part of the block
+        match text.strip_prefix('\n') {
+            Some(a) if out.ends_with("```\n") => out.push_str(a),
+            _ => out.push_str(&text),
+        }
+        return;
+    }
+    let mut collapsed = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.starts_with(char::is_whitespace) && !collapsed.is_empty() {
+        collapsed.insert(0, ' ');
+    }
+    if text.ends_with(char::is_whitespace) && !collapsed.is_empty() {
+        collapsed.push(' ');
+    }
+    if out.is_empty() || out.ends_with('\n') || out.ends_with(' ') {
+        collapsed = collapsed.trim_start().to_owned();
+    }
+    out.push_str(&collapsed);
+}
+
+fn decode_html_entities(text: &str) -> String {
+    let re = Regex::new(r"&(?<entity>#[0-9]+|#[xX][0-9a-fA-F]+|[a-zA-Z]+);").unwrap();
+    re.replace_all(text, |caps: &regex::Captures| {
+        let entity = &caps["entity"];
+        let decoded = match entity {
+            "nbsp" => Some(' '),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "amp" => Some('&'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "le" => Some('≤'),
+            "ge" => Some('≥'),
+            "ne" => Some('≠'),
+            "times" => Some('×'),
+            "minus" => Some('−'),
+            "rarr" => Some('→'),
+            _ => {
+                let num = if let Some(hex) = entity.strip_prefix("#x").or(entity.strip_prefix("#X"))
+                {
+                    u32::from_str_radix(hex, 16).ok()
+                } else {
+                    entity.strip_prefix('#').and_then(|a| a.parse::<u32>().ok())
+                };
+                num.and_then(char::from_u32)
+            }
+        };
+        match decoded {
+            Some(c) => c.to_string(),
+            None => caps[0].to_owned(),
+        }
+    })
+    .into_owned()
 }
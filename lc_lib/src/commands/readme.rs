@@ -0,0 +1,169 @@
+// the progress index kept in the repo's top-level README
+use anyhow::Result;
+use std::collections::BTreeMap;
+use strum::IntoEnumIterator;
+
+use super::error::Error;
+use super::info::{load_all, Difficulty, ProblemInfo, Status};
+use super::repo::Repo;
+
+pub const START_MARKER: &str = "<!-- lc-readme:start -->";
+pub const END_MARKER: &str = "<!-- lc-readme:end -->";
+/// Set to anything but `0` to regenerate the README after `new`, `finish` and `tag`.
+pub const HOOK_VAR: &str = "LEETCODE_README_HOOK";
+
+/// A shields.io badge with `label` on the left and `message` on the right.
+fn badge(label: &str, message: &str, color: &str) -> String {
+    let escape = |a: &str| a.replace('-', "--").replace('_', "__").replace(' ', "%20");
+    format!(
+        "![{label}: {message}](https://img.shields.io/badge/{}-{}-{color})",
+        escape(label),
+        escape(message).replace('/', "%2F")
+    )
+}
+
+fn md_cell(cell: &str) -> String {
+    cell.replace('|', "\\|")
+}
+
+/// The generated part of the README, including the markers around it.
+pub fn render_section(problems: &[ProblemInfo]) -> String {
+    let count = |status: Status| problems.iter().filter(|a| a.meta.status == status).count();
+    let mut out = format!("{START_MARKER}\n## Progress\n\n");
+    out.push_str(&format!(
+        "**{}** problems tracked: {} finished, {} attempted, {} hidden.\n\n",
+        problems.len(),
+        count(Status::Finished),
+        count(Status::Attempted),
+        count(Status::Hidden)
+    ));
+
+    let badges = Difficulty::iter()
+        .map(|difficulty| {
+            let of = problems
+                .iter()
+                .filter(|a| a.meta.difficulty == Some(difficulty))
+                .collect::<Vec<_>>();
+            let finished = of
+                .iter()
+                .filter(|a| a.meta.status == Status::Finished)
+                .count();
+            let color = match difficulty {
+                Difficulty::Easy => "brightgreen",
+                Difficulty::Medium => "orange",
+                Difficulty::Hard => "red",
+            };
+            badge(
+                &difficulty.to_string(),
+                &format!("{finished}/{}", of.len()),
+                color,
+            )
+        })
+        .collect::<Vec<_>>();
+    out.push_str(&badges.join(" "));
+    out.push_str("\n\n");
+
+    // tag -> (tracked, finished)
+    let mut tags: BTreeMap<&str, (usize, usize)> = BTreeMap::new();
+    for prob in problems {
+        for tag in prob.tags.iter() {
+            let entry = tags.entry(tag).or_default();
+            entry.0 += 1;
+            entry.1 += usize::from(prob.meta.status == Status::Finished);
+        }
+    }
+    if !tags.is_empty() {
+        out.push_str("### By Tag\n\n| Tag | Tracked | Finished |\n|---|--:|--:|\n");
+        for (tag, (tracked, finished)) in tags {
+            out.push_str(&format!("| {} | {tracked} | {finished} |\n", md_cell(tag)));
+        }
+        out.push('\n');
+    }
+
+    out.push_str(
+        "### Problems\n\n| # | Problem | Difficulty | Status | Tags |\n|--:|---|---|---|---|\n",
+    );
+    let mut sorted = problems.iter().collect::<Vec<_>>();
+    sorted.sort_by_key(|a| a.number);
+    for prob in sorted {
+        let title = match prob.title.is_empty() {
+            true => format!("Problem {}", prob.number),
+            false => md_cell(&prob.title),
+        };
+        out.push_str(&format!(
+            "| {} | [{title}](src/{}/) | {} | {} | {} |\n",
+            prob.number,
+            prob.number,
+            prob.meta
+                .difficulty
+                .map(|a| a.to_string())
+                .unwrap_or_default(),
+            prob.meta.status,
+            md_cell(&prob.tags.iter().cloned().collect::<Vec<_>>().join(", "))
+        ));
+    }
+    out.push_str(END_MARKER);
+    out
+}
+
+/// Replaces what is between the markers in `readme` with `section`, which has its own markers.
+/// The section is appended when the README has no markers yet.
+pub fn splice(readme: &str, section: &str) -> Result<String> {
+    let (Some(start), Some(end)) = (readme.find(START_MARKER), readme.find(END_MARKER)) else {
+        if readme.contains(START_MARKER) || readme.contains(END_MARKER) {
+            return Err(Error::parse(format!(
+                "The README has only one of `{START_MARKER}` and `{END_MARKER}`"
+            ))
+            .into());
+        }
+        let mut out = readme.trim_end().to_owned();
+        if !out.is_empty() {
+            out.push_str("\n\n");
+        }
+        out.push_str(section);
+        out.push('\n');
+        return Ok(out);
+    };
+    if end < start {
+        return Err(Error::parse(format!(
+            "`{END_MARKER}` comes before `{START_MARKER}` in the README"
+        ))
+        .into());
+    }
+    Ok(format!(
+        "{}{section}{}",
+        &readme[..start],
+        &readme[end + END_MARKER.len()..]
+    ))
+}
+
+/// Regenerates the progress section of the top-level README.
+pub fn update(repo: &Repo) -> Result<()> {
+    let path = repo.readme();
+    let readme = repo.read_optional(&path)?.unwrap_or_default();
+    let updated = splice(&readme, &render_section(&load_all(repo)?))?;
+    if updated != readme {
+        repo.write(&path, &updated)?;
+    }
+    Ok(())
+}
+
+pub fn hook_enabled() -> bool {
+    std::env::var(HOOK_VAR).is_ok_and(|a| !a.is_empty() && a != "0")
+}
+
+/// Regenerates the README when the hook is turned on with `$LEETCODE_README_HOOK`. The command
+/// that ran before it already succeeded, so a failure here is only reported.
+pub fn hook(repo: &Repo) {
+    if hook_enabled() {
+        if let Err(e) = update(repo) {
+            println!("Could not update the README: {e}");
+        }
+    }
+}
+
+pub fn run(repo: &Repo) -> Result<()> {
+    update(repo)?;
+    println!("Updated {}", repo.readme().display());
+    Ok(())
+}
@@ -0,0 +1,230 @@
+// the repo of solutions, which every command goes through to read and write its files
+use anyhow::Result;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use super::error::Error;
+use super::fs;
+use super::store::{FsStore, MemoryStore, Store};
+
+/// The top of the repo, set to skip discovery.
+pub const DIR_VAR: &str = "LEETCODE_DIR";
+/// A file at the top of the repo, found by walking up from the current directory.
+pub const MARKER: &str = ".lc";
+
+/// `$XDG_CONFIG_HOME/lc/config.json`, or `~/.config/lc/config.json`.
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct Config {
+    /// The top of the repo
+    pub repo: Option<PathBuf>,
+}
+
+impl Config {
+    pub fn path() -> Option<PathBuf> {
+        match std::env::var("XDG_CONFIG_HOME") {
+            Ok(a) if !a.is_empty() => Some(PathBuf::from(a).join("lc").join("config.json")),
+            _ => std::env::var("HOME").ok().map(|a| {
+                PathBuf::from(a)
+                    .join(".config")
+                    .join("lc")
+                    .join("config.json")
+            }),
+        }
+    }
+
+    /// Reads the config, the defaults when there is none.
+    pub fn read() -> Result<Config> {
+        let Some(path) = Config::path() else {
+            return Ok(Config::default());
+        };
+        match fs::read_optional(&path)? {
+            Some(a) => serde_json::from_str(&a).map_err(|e| {
+                Error::config(format!("{} is not a valid config", path.display()))
+                    .caused_by(e)
+                    .into()
+            }),
+            None => Ok(Config::default()),
+        }
+    }
+}
+
+/// A repo of solutions laid out as
+/// `Cargo.toml`, `CUSTOM_TAGS`, `CATALOG.json`, `README.md` and `src/<num>/`, where each problem
+/// has `README.md`, `TAGS`, `META.json` and its solutions in `src/`.
+///
+/// Its files are kept in a `Store`, the disk unless made with `with_store` or `in_memory`. Clones
+/// share the same store.
+#[derive(Debug, Clone)]
+pub struct Repo {
+    root: PathBuf,
+    store: Arc<dyn Store>,
+}
+
+impl Repo {
+    /// The repo on disk at `root`.
+    pub fn at(root: impl Into<PathBuf>) -> Repo {
+        Repo::with_store(root, FsStore)
+    }
+
+    pub fn with_store(root: impl Into<PathBuf>, store: impl Store + 'static) -> Repo {
+        Repo {
+            root: root.into(),
+            store: Arc::new(store),
+        }
+    }
+
+    /// An empty repo that only lives in memory, with a `Cargo.toml` and no problems.
+    ///
+    /// Everything that goes through the repo works on it, but the commands that run `cargo` or
+    /// `git` in it still need one on disk.
+    pub fn in_memory() -> Repo {
+        let repo = Repo::with_store("/leetcode", MemoryStore::new());
+        repo.create_dir_all(&repo.problems_dir())
+            .and_then(|_| {
+                repo.write(
+                    &repo.cargo_toml(),
+                    "[package]\nname = \"leetcode\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+                )
+            })
+            .expect("writing to memory can't fail");
+        repo
+    }
+
+    /// Finds the repo from `$LEETCODE_DIR`, then the `repo` of the config file, then the closest
+    /// directory above the current one holding a `.lc` file.
+    pub fn open() -> Result<Repo> {
+        if let Ok(a) = std::env::var(DIR_VAR) {
+            if !a.is_empty() {
+                return Ok(Repo::at(a));
+            }
+        }
+        if let Some(a) = Config::read()?.repo {
+            return Ok(Repo::at(a));
+        }
+        let cwd = std::env::current_dir().map_err(|e| Error::filesystem(".", e))?;
+        match Repo::find(&cwd) {
+            Some(a) => Ok(a),
+            None => Err(Error::config(format!(
+                "Could not find the repo, set {DIR_VAR}, add `repo` to {} or create a `{MARKER}` \
+                 file at its top",
+                Config::path()
+                    .map(|a| a.display().to_string())
+                    .unwrap_or("the config".to_owned())
+            ))
+            .into()),
+        }
+    }
+
+    /// The closest directory from `dir` upwards that holds the marker file.
+    pub fn find(dir: &Path) -> Option<Repo> {
+        dir.ancestors()
+            .find(|a| a.join(MARKER).is_file())
+            .map(Repo::at)
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn cargo_toml(&self) -> PathBuf {
+        self.root.join("Cargo.toml")
+    }
+
+    pub fn custom_tags(&self) -> PathBuf {
+        self.root.join("CUSTOM_TAGS")
+    }
+
+    pub fn catalog(&self) -> PathBuf {
+        self.root.join("CATALOG.json")
+    }
+
+    /// The top-level README with the progress index.
+    pub fn readme(&self) -> PathBuf {
+        self.root.join("README.md")
+    }
+
+    /// The directory holding one directory per problem.
+    pub fn problems_dir(&self) -> PathBuf {
+        self.root.join("src")
+    }
+
+    pub fn problem_dir(&self, num: usize) -> PathBuf {
+        self.problems_dir().join(num.to_string())
+    }
+
+    /// The directory holding the solutions of problem `num`.
+    pub fn solution_dir(&self, num: usize) -> PathBuf {
+        self.problem_dir(num).join("src")
+    }
+
+    pub fn main_rs(&self, num: usize) -> PathBuf {
+        self.solution_dir(num).join("main.rs")
+    }
+
+    pub fn problem_readme(&self, num: usize) -> PathBuf {
+        self.problem_dir(num).join("README.md")
+    }
+
+    pub fn tags(&self, num: usize) -> PathBuf {
+        self.problem_dir(num).join("TAGS")
+    }
+
+    pub fn meta(&self, num: usize) -> PathBuf {
+        self.problem_dir(num).join("META.json")
+    }
+
+    /// `path` relative to the top of the repo, as git and the README links want it.
+    pub fn relative<'a>(&self, path: &'a Path) -> &'a Path {
+        path.strip_prefix(&self.root).unwrap_or(path)
+    }
+
+    pub fn read(&self, path: &Path) -> Result<String, Error> {
+        self.store.read(path)
+    }
+
+    /// Reads `path`, `None` when there is no such file.
+    pub fn read_optional(&self, path: &Path) -> Result<Option<String>, Error> {
+        self.store.read_optional(path)
+    }
+
+    pub fn write(&self, path: &Path, contents: &str) -> Result<(), Error> {
+        self.store.write(path, contents)
+    }
+
+    /// Replaces `path` without ever leaving a partial file behind.
+    pub fn replace(&self, path: &Path, contents: &str) -> Result<(), Error> {
+        self.store.replace(path, contents)
+    }
+
+    pub fn append(&self, path: &Path, contents: &str) -> Result<(), Error> {
+        self.store.append(path, contents)
+    }
+
+    pub fn create_dir_all(&self, path: &Path) -> Result<(), Error> {
+        self.store.create_dir_all(path)
+    }
+
+    pub fn remove_file(&self, path: &Path) -> Result<(), Error> {
+        self.store.remove_file(path)
+    }
+
+    pub fn exists(&self, path: &Path) -> bool {
+        self.store.exists(path)
+    }
+
+    pub fn is_dir(&self, path: &Path) -> bool {
+        self.store.is_dir(path)
+    }
+
+    /// What is directly in `dir`, sorted.
+    pub fn list(&self, dir: &Path) -> Result<Vec<PathBuf>, Error> {
+        self.store.list(dir)
+    }
+
+    /// When `path` was last written, `None` when that can't be told.
+    pub fn modified(&self, path: &Path) -> Option<SystemTime> {
+        self.store.modified(path)
+    }
+}
@@ -0,0 +1,173 @@
+// sending requests to LeetCode without hammering it, and making sense of how they fail
+use anyhow::Result;
+use reqwest::{header, StatusCode};
+use std::hash::{BuildHasher, Hasher};
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+use super::error::Error;
+
+/// How many times a request is sent before giving up on a transient failure.
+const MAX_ATTEMPTS: u32 = 4;
+/// The first backoff, doubled for every retry after it.
+const BASE_BACKOFF: Duration = Duration::from_millis(500);
+/// The longest a `Retry-After` is waited for before giving up instead.
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(60);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+/// The least time between two requests from the whole process, so batches stay polite.
+const MIN_INTERVAL: Duration = Duration::from_millis(250);
+
+static LAST_REQUEST: Mutex<Option<Instant>> = Mutex::const_new(None);
+
+/// The message of `Error::Auth` when LeetCode turns the login down.
+const AUTH_MESSAGE: &str =
+    "LeetCode did not accept the login, refresh LEETCODE_SESSION and LEETCODE_TOKEN";
+/// The message of `Error::NotFound` when LeetCode has nothing at the address.
+const NOT_FOUND_MESSAGE: &str = "LeetCode has no such problem or page";
+
+/// Reads the typed error out of a GraphQL response that has an `errors` list.
+pub fn graphql_error(resp: &serde_json::Value) -> Option<Error> {
+    let messages = resp["errors"]
+        .as_array()?
+        .iter()
+        .filter_map(|a| a["message"].as_str())
+        .collect::<Vec<_>>()
+        .join("; ");
+    let lower = messages.to_lowercase();
+    Some(if lower.contains("premium") || lower.contains("subscri") {
+        Error::PremiumOnly
+    } else if lower.contains("not exist") || lower.contains("not found") {
+        Error::not_found(NOT_FOUND_MESSAGE)
+    } else if lower.contains("login") || lower.contains("authenticat") {
+        Error::auth(AUTH_MESSAGE)
+    } else {
+        Error::network(format!("LeetCode answered with an error: {messages}"))
+    })
+}
+
+/// How long a `Retry-After` header asks to wait, given in seconds or as an HTTP date.
+pub fn retry_after(value: &str, now: chrono::DateTime<chrono::Utc>) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let at = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    Some(
+        (at.with_timezone(&chrono::Utc) - now)
+            .to_std()
+            .unwrap_or_default(),
+    )
+}
+
+/// The backoff before retry `attempt` (from 0), doubling each time with up to half of it added
+/// at random so that batches don't retry in lockstep.
+pub fn backoff(attempt: u32, random: u64) -> Duration {
+    let base = BASE_BACKOFF * 2u32.pow(attempt);
+    base + base.mul_f64((random % 1000) as f64 / 2000.0)
+}
+
+fn random() -> u64 {
+    let mut hasher = std::collections::hash_map::RandomState::new().build_hasher();
+    hasher.write_u128(Instant::now().elapsed().as_nanos());
+    hasher.finish()
+}
+
+/// Waits until enough time has passed since the last request anywhere in the process.
+async fn wait_turn() {
+    let mut last = LAST_REQUEST.lock().await;
+    if let Some(at) = *last {
+        let since = at.elapsed();
+        if since < MIN_INTERVAL {
+            tokio::time::sleep(MIN_INTERVAL - since).await;
+        }
+    }
+    *last = Some(Instant::now());
+}
+
+/// Still rate limited or failing after every retry.
+fn status_error(status: StatusCode) -> Error {
+    Error::network(format!("LeetCode answered with {status}"))
+}
+
+fn is_transient(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+/// Sends `request` and parses the JSON it gets back. Rate limiting and transient failures are
+/// retried with backoff, honouring `Retry-After`. Server errors and timeouts are only retried when
+/// `idempotent` is set, since sending a submission twice would judge it twice.
+pub async fn send_json(
+    request: reqwest::RequestBuilder,
+    idempotent: bool,
+) -> Result<serde_json::Value> {
+    let mut attempt = 0;
+    loop {
+        let Some(this) = request.try_clone() else {
+            return Err(Error::network("A request with a streamed body can't be sent").into());
+        };
+        wait_turn().await;
+        let last_attempt = attempt + 1 >= MAX_ATTEMPTS;
+        let resp = match this.timeout(REQUEST_TIMEOUT).send().await {
+            Ok(a) => a,
+            Err(e) if idempotent && !last_attempt && (e.is_timeout() || e.is_connect()) => {
+                tokio::time::sleep(backoff(attempt, random())).await;
+                attempt += 1;
+                continue;
+            }
+            Err(e) => {
+                return Err(Error::network("Could not reach LeetCode")
+                    .caused_by(e)
+                    .into())
+            }
+        };
+
+        let status = resp.status();
+        let retry = status == StatusCode::TOO_MANY_REQUESTS || idempotent;
+        if is_transient(status) && retry && !last_attempt {
+            let wait = resp
+                .headers()
+                .get(header::RETRY_AFTER)
+                .and_then(|a| a.to_str().ok())
+                .and_then(|a| retry_after(a, chrono::Utc::now()))
+                .unwrap_or(backoff(attempt, random()));
+            if wait > MAX_RETRY_AFTER {
+                return Err(status_error(status).into());
+            }
+            tokio::time::sleep(wait).await;
+            attempt += 1;
+            continue;
+        }
+
+        let content_type = resp
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|a| a.to_str().ok())
+            .unwrap_or("")
+            .to_owned();
+        match status {
+            StatusCode::UNAUTHORIZED => return Err(Error::auth(AUTH_MESSAGE).into()),
+            StatusCode::FORBIDDEN if content_type.contains("json") => {
+                return Err(Error::auth(AUTH_MESSAGE).into())
+            }
+            StatusCode::NOT_FOUND => return Err(Error::not_found(NOT_FOUND_MESSAGE).into()),
+            _ => {}
+        }
+        if !content_type.contains("json") {
+            // usually Cloudflare's challenge page
+            return Err(Error::network(format!(
+                "LeetCode answered with {status} and `{content_type}` instead of JSON, it may be \
+                 blocking requests for a while"
+            ))
+            .into());
+        }
+        if !status.is_success() {
+            return Err(status_error(status).into());
+        }
+        return match resp.json().await {
+            Ok(a) => Ok(a),
+            Err(e) => Err(Error::parse("LeetCode answered with invalid JSON")
+                .caused_by(e)
+                .into()),
+        };
+    }
+}
@@ -0,0 +1,197 @@
+// spaced repetition of finished problems so their solutions aren't forgotten
+use anyhow::Result;
+use chrono::{DateTime, Days, Local, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::io::IsTerminal;
+
+use super::common::prompt_for_input;
+use super::info::{load, load_all, Difficulty, ProblemInfo, Status};
+use super::local::run_tests;
+use super::repo::Repo;
+
+/// The ease every schedule starts with, and the lowest it can drop to, as in SM-2.
+const INITIAL_EASE: f64 = 2.5;
+const MINIMUM_EASE: f64 = 1.3;
+
+/// When a finished problem is next due to be re-solved, stored in its `META.json`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Review {
+    /// How many reviews in a row were recalled, reset by a failed review
+    pub repetitions: u32,
+    pub interval_days: u64,
+    pub ease: f64,
+    pub due: NaiveDate,
+    pub last_reviewed: Option<DateTime<Utc>>,
+    /// Set by `lc review <num> --start` to time the re-solve
+    pub started: Option<DateTime<Utc>>,
+}
+
+impl Review {
+    /// The schedule of a problem that was just finished on `day`: due again the next day.
+    pub fn new(day: NaiveDate) -> Review {
+        Review {
+            repetitions: 0,
+            interval_days: 0,
+            ease: INITIAL_EASE,
+            due: day + Days::new(1),
+            last_reviewed: None,
+            started: None,
+        }
+    }
+
+    /// The schedule of a problem, starting one from when it was finished if it has none yet.
+    pub fn of(prob: &ProblemInfo) -> Option<Review> {
+        if prob.meta.status != Status::Finished {
+            return None;
+        }
+        match &prob.meta.review {
+            Some(a) => Some(a.clone()),
+            None => {
+                let finished = prob.meta.finished.unwrap_or(Utc::now());
+                Some(Review::new(finished.with_timezone(&Local).date_naive()))
+            }
+        }
+    }
+
+    /// Reschedules after a review on `day` with a recall `grade` from 0 (blackout) to 5
+    /// (perfect), following SM-2.
+    pub fn grade(&mut self, grade: u8, day: NaiveDate) {
+        let grade = grade.min(5);
+        if grade >= 3 {
+            self.interval_days = match self.repetitions {
+                0 => 1,
+                1 => 6,
+                _ => (self.interval_days as f64 * self.ease).round() as u64,
+            };
+            self.repetitions += 1;
+        } else {
+            self.repetitions = 0;
+            self.interval_days = 1;
+        }
+        let miss = (5 - grade) as f64;
+        self.ease = (self.ease + 0.1 - miss * (0.08 + miss * 0.02)).max(MINIMUM_EASE);
+        self.due = day + Days::new(self.interval_days);
+        self.started = None;
+    }
+}
+
+/// A recall grade for a re-solve from whether its tests passed and how many minutes it took,
+/// compared to how long a problem of that difficulty should take.
+pub fn derived_grade(passed: bool, minutes: Option<i64>, difficulty: Option<Difficulty>) -> u8 {
+    if !passed {
+        return 2;
+    }
+    let target = match difficulty {
+        Some(Difficulty::Easy) => 15,
+        Some(Difficulty::Medium) | None => 30,
+        Some(Difficulty::Hard) => 60,
+    };
+    match minutes {
+        Some(a) if a <= target => 5,
+        Some(a) if a <= target * 2 => 4,
+        Some(_) => 3,
+        // without a start time there's nothing to say it was slow
+        None => 4,
+    }
+}
+
+/// Every finished problem due for review on or before `day`, most overdue first.
+pub fn due(repo: &Repo, day: NaiveDate) -> Result<Vec<(ProblemInfo, Review)>> {
+    let mut out = load_all(repo)?
+        .into_iter()
+        .filter_map(|prob| Review::of(&prob).map(|review| (prob, review)))
+        .filter(|(_, review)| review.due <= day)
+        .collect::<Vec<_>>();
+    out.sort_by_key(|(prob, review)| (review.due, prob.number));
+    Ok(out)
+}
+
+fn schedule(repo: &Repo, num: usize) -> Result<(ProblemInfo, Review)> {
+    let prob = load(repo, num)?;
+    match Review::of(&prob) {
+        Some(review) => Ok((prob, review)),
+        None => Err(anyhow::Error::msg(format!(
+            "Problem {num} isn't finished so it has nothing to review"
+        ))),
+    }
+}
+
+/// Records that a re-solve of problem `num` is starting, so its time can count towards the grade.
+pub fn start(repo: &Repo, num: usize) -> Result<()> {
+    let (mut prob, mut review) = schedule(repo, num)?;
+    review.started = Some(Utc::now());
+    prob.meta.review = Some(review);
+    prob.meta.write(repo, num)?;
+    println!("Started reviewing Problem {num}, run `lc review {num}` once it is solved again");
+    Ok(())
+}
+
+/// Grades the re-solve of problem `num` and reschedules it. Without a `grade` one is worked out
+/// from the problem's tests and time, which can be overridden at the prompt.
+pub fn finish(repo: &Repo, num: usize, grade: Option<u8>) -> Result<Review> {
+    let (mut prob, mut review) = schedule(repo, num)?;
+    let grade = match grade {
+        Some(a) => a,
+        None => {
+            let summary = run_tests(repo, num)?;
+            let minutes = review
+                .started
+                .map(|started| (Utc::now() - started).num_minutes());
+            let derived = derived_grade(summary.success(), minutes, prob.meta.difficulty);
+            println!("Tests: {}", summary.compact());
+            if let Some(a) = minutes {
+                println!("Time: {a} minute(s)");
+            }
+            if std::io::stdin().is_terminal() {
+                let (input, _) = prompt_for_input::<String>(&format!(
+                    "Recall grade from 0 (forgot) to 5 (perfect) [{derived}]: "
+                ))?;
+                if input.is_empty() {
+                    derived
+                } else {
+                    input.parse::<u8>()?
+                }
+            } else {
+                derived
+            }
+        }
+    };
+    if grade > 5 {
+        return Err(anyhow::Error::msg(format!(
+            "A recall grade is from 0 to 5, not {grade}"
+        )));
+    }
+    review.grade(grade, Local::now().date_naive());
+    review.last_reviewed = Some(Utc::now());
+    prob.meta.review = Some(review.clone());
+    prob.meta.write(repo, num)?;
+    Ok(review)
+}
+
+pub fn run(repo: &Repo, num: Option<usize>, start_review: bool, grade: Option<u8>) -> Result<()> {
+    let Some(num) = num else {
+        let today = Local::now().date_naive();
+        let due = due(repo, today)?;
+        if due.is_empty() {
+            println!("Nothing is due for review today");
+        }
+        for (prob, review) in due {
+            let overdue = (today - review.due).num_days();
+            let when = match overdue {
+                0 => "due today".to_owned(),
+                a => format!("{a} day(s) overdue"),
+            };
+            println!("{}. {} ({when})", prob.number, prob.title);
+        }
+        return Ok(());
+    };
+    if start_review {
+        return start(repo, num);
+    }
+    let review = finish(repo, num, grade)?;
+    println!(
+        "Problem {num} is next due on {} (in {} day(s))",
+        review.due, review.interval_days
+    );
+    Ok(())
+}
@@ -0,0 +1,241 @@
+// progress analytics computed from the problems tracked in the repo
+use anyhow::Result;
+use chrono::{DateTime, Datelike, Local, NaiveDate, Utc};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use strum::IntoEnumIterator;
+
+use super::info::{load_all, Difficulty, ProblemInfo, Status};
+use super::repo::Repo;
+
+/// How many weeks and months are charted when printing, the JSON output has all of them.
+const RECENT_PERIODS: usize = 12;
+const BAR_WIDTH: usize = 40;
+
+/// Overall progress across every tracked problem.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Stats {
+    pub total: usize,
+    pub by_status: BTreeMap<String, usize>,
+    pub by_difficulty: BTreeMap<String, usize>,
+    pub by_tag: BTreeMap<String, usize>,
+    pub by_language: BTreeMap<String, usize>,
+    /// Problems finished per ISO week, keyed like `2024-W07`
+    pub solved_per_week: BTreeMap<String, usize>,
+    /// Problems finished per month, keyed like `2024-02`
+    pub solved_per_month: BTreeMap<String, usize>,
+    /// Days in a row, up to today, with at least one problem finished. A streak is still current
+    /// when nothing has been finished yet today.
+    pub current_streak: usize,
+    pub longest_streak: usize,
+    /// Days in a row with the daily challenge started through `lc daily` and finished
+    pub current_daily_streak: usize,
+    pub longest_daily_streak: usize,
+    /// Average hours from creating a problem to finishing it
+    pub average_hours_to_finish: BTreeMap<String, f64>,
+}
+
+impl Stats {
+    /// Computes the statistics of `problems` as of `now`. Finishes are counted by local day but
+    /// daily challenges change at midnight UTC, so their streak goes by the UTC day. Languages
+    /// depend on the files in the repo, so they are left to [`collect`].
+    pub fn compute(problems: &[ProblemInfo], now: DateTime<Utc>) -> Stats {
+        let mut out = Stats {
+            total: problems.len(),
+            ..Default::default()
+        };
+        for status in Status::iter() {
+            out.by_status.insert(status.to_string(), 0);
+        }
+        for difficulty in Difficulty::iter() {
+            out.by_difficulty.insert(difficulty.to_string(), 0);
+        }
+
+        let mut finish_days = Vec::new();
+        let mut daily_days = Vec::new();
+        let mut durations: BTreeMap<String, Vec<f64>> = BTreeMap::new();
+        for prob in problems {
+            *out.by_status
+                .entry(prob.meta.status.to_string())
+                .or_default() += 1;
+            let difficulty = prob
+                .meta
+                .difficulty
+                .map(|a| a.to_string())
+                .unwrap_or("Unknown".to_owned());
+            *out.by_difficulty.entry(difficulty.clone()).or_default() += 1;
+            for tag in prob.tags.iter() {
+                *out.by_tag.entry(tag.clone()).or_default() += 1;
+            }
+
+            if prob.meta.status != Status::Finished {
+                continue;
+            }
+            daily_days.extend(prob.meta.dailies.iter().copied());
+            let Some(finished) = prob.meta.finished else {
+                continue;
+            };
+            let day = finished.with_timezone(&Local).date_naive();
+            let week = day.iso_week();
+            *out.solved_per_week
+                .entry(format!("{}-W{:02}", week.year(), week.week()))
+                .or_default() += 1;
+            *out.solved_per_month
+                .entry(day.format("%Y-%m").to_string())
+                .or_default() += 1;
+            finish_days.push(day);
+            if let Some(created) = prob.meta.created {
+                let hours = (finished - created).num_minutes() as f64 / 60.0;
+                durations
+                    .entry(difficulty)
+                    .or_default()
+                    .push(hours.max(0.0));
+            }
+        }
+
+        let today = now.with_timezone(&Local).date_naive();
+        (out.current_streak, out.longest_streak) = streaks(finish_days, today);
+        (out.current_daily_streak, out.longest_daily_streak) =
+            streaks(daily_days, now.date_naive());
+        out.average_hours_to_finish = durations
+            .into_iter()
+            .map(|(difficulty, hours)| (difficulty, hours.iter().sum::<f64>() / hours.len() as f64))
+            .collect();
+        out
+    }
+}
+
+/// The current and longest runs of consecutive days in `days`.
+pub fn streaks(mut days: Vec<NaiveDate>, today: NaiveDate) -> (usize, usize) {
+    days.sort();
+    days.dedup();
+    let mut longest = 0;
+    let mut run = 0;
+    let mut previous: Option<NaiveDate> = None;
+    for day in &days {
+        run = match previous {
+            Some(a) if a.succ_opt() == Some(*day) => run + 1,
+            _ => 1,
+        };
+        longest = longest.max(run);
+        previous = Some(*day);
+    }
+    let current = match days.last() {
+        Some(last) if *last == today || last.succ_opt() == Some(today) => run,
+        _ => 0,
+    };
+    (current, longest)
+}
+
+/// The languages a problem has been solved in, from the extensions of its solution files.
+fn languages(repo: &Repo, num: usize) -> Result<Vec<&'static str>> {
+    let mut out = Vec::new();
+    for path in repo.list(&repo.solution_dir(num))? {
+        let language = match path.extension().and_then(|a| a.to_str()) {
+            Some("rs") => "Rust",
+            Some("py") => "Python",
+            Some("cpp" | "cc") => "C++",
+            Some("c") => "C",
+            Some("java") => "Java",
+            Some("go") => "Go",
+            Some("js") => "JavaScript",
+            Some("ts") => "TypeScript",
+            _ => continue,
+        };
+        if !out.contains(&language) {
+            out.push(language);
+        }
+    }
+    Ok(out)
+}
+
+/// Computes the statistics of every problem in the repo.
+pub fn collect(repo: &Repo) -> Result<Stats> {
+    let problems = load_all(repo)?;
+    let mut out = Stats::compute(&problems, Utc::now());
+    for prob in &problems {
+        for language in languages(repo, prob.number).unwrap_or_default() {
+            *out.by_language.entry(language.to_owned()).or_default() += 1;
+        }
+    }
+    Ok(out)
+}
+
+/// Formats a number of hours like `3d 4h` or `45m`.
+fn duration(hours: f64) -> String {
+    let minutes = (hours * 60.0).round() as u64;
+    match (minutes / (24 * 60), minutes / 60 % 24, minutes % 60) {
+        (0, 0, m) => format!("{m}m"),
+        (0, h, m) => format!("{h}h {m}m"),
+        (d, h, _) => format!("{d}d {h}h"),
+    }
+}
+
+/// Prints `rows` as a table with a bar for each count, scaled to the largest one.
+fn print_chart<'a>(title: &str, rows: impl IntoIterator<Item = (&'a String, &'a usize)>) {
+    let rows = rows.into_iter().collect::<Vec<_>>();
+    println!("{title}");
+    if rows.is_empty() {
+        println!("  (none)");
+    }
+    let label_width = rows.iter().map(|a| a.0.len()).max().unwrap_or(0);
+    let max = rows.iter().map(|a| *a.1).max().unwrap_or(0).max(1);
+    for (label, count) in rows {
+        let bar = "█".repeat((count * BAR_WIDTH).div_ceil(max));
+        println!("  {label:<label_width$} {count:>4} {bar}");
+    }
+    println!();
+}
+
+/// Only the last `n` entries of a map keyed by date.
+fn recent(map: &BTreeMap<String, usize>, n: usize) -> impl Iterator<Item = (&String, &usize)> {
+    map.iter().skip(map.len().saturating_sub(n))
+}
+
+pub fn run(repo: &Repo, json: bool) -> Result<()> {
+    let stats = collect(repo)?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&stats)?);
+        return Ok(());
+    }
+
+    println!("Total problems: {}\n", stats.total);
+    print_chart("Status", &stats.by_status);
+    // easiest first rather than alphabetically
+    let mut difficulties = stats.by_difficulty.iter().collect::<Vec<_>>();
+    difficulties.sort_by_key(|a| {
+        let difficulty = a.0.parse::<Difficulty>().ok();
+        (difficulty.is_none(), difficulty)
+    });
+    print_chart("Difficulty", difficulties);
+    let mut tags = stats.by_tag.iter().collect::<Vec<_>>();
+    tags.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+    print_chart("Tags", tags);
+    print_chart("Languages", &stats.by_language);
+    print_chart(
+        "Solved per week",
+        recent(&stats.solved_per_week, RECENT_PERIODS),
+    );
+    print_chart(
+        "Solved per month",
+        recent(&stats.solved_per_month, RECENT_PERIODS),
+    );
+
+    println!("Average time to finish");
+    if stats.average_hours_to_finish.is_empty() {
+        println!("  (none)");
+    }
+    for (difficulty, hours) in &stats.average_hours_to_finish {
+        println!("  {difficulty:<7} {}", duration(*hours));
+    }
+    println!();
+    println!(
+        "Streak: {} day(s), longest {} day(s)",
+        stats.current_streak, stats.longest_streak
+    );
+    println!(
+        "Daily challenge streak: {} day(s), longest {} day(s)",
+        stats.current_daily_streak, stats.longest_daily_streak
+    );
+    Ok(())
+}
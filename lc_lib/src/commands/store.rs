@@ -0,0 +1,248 @@
+// where the files of a repo are kept, on disk or in memory for tests
+use std::collections::BTreeMap;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use super::error::Error;
+use super::fs;
+
+/// The file operations a `Repo` needs. Paths are the full paths the `Repo` builds from its root.
+pub trait Store: std::fmt::Debug + Send + Sync {
+    fn read(&self, path: &Path) -> Result<String, Error>;
+
+    /// Reads `path`, `None` when there is no such file.
+    fn read_optional(&self, path: &Path) -> Result<Option<String>, Error> {
+        match self.read(path) {
+            Ok(a) => Ok(Some(a)),
+            Err(Error::Filesystem { source, .. }) if source.kind() == ErrorKind::NotFound => {
+                Ok(None)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    fn write(&self, path: &Path, contents: &str) -> Result<(), Error>;
+
+    /// Replaces `path` without ever leaving a partial file behind.
+    fn replace(&self, path: &Path, contents: &str) -> Result<(), Error> {
+        self.write(path, contents)
+    }
+
+    /// Appends to `path`, which must already exist.
+    fn append(&self, path: &Path, contents: &str) -> Result<(), Error>;
+
+    fn create_dir_all(&self, path: &Path) -> Result<(), Error>;
+
+    fn remove_file(&self, path: &Path) -> Result<(), Error>;
+
+    fn exists(&self, path: &Path) -> bool;
+
+    fn is_dir(&self, path: &Path) -> bool;
+
+    /// What is directly in `dir`, sorted.
+    fn list(&self, dir: &Path) -> Result<Vec<PathBuf>, Error>;
+
+    /// When `path` was last written, `None` when that can't be told.
+    fn modified(&self, path: &Path) -> Option<SystemTime>;
+}
+
+/// The files on disk.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FsStore;
+
+impl Store for FsStore {
+    fn read(&self, path: &Path) -> Result<String, Error> {
+        fs::read_to_string(path)
+    }
+
+    fn read_optional(&self, path: &Path) -> Result<Option<String>, Error> {
+        fs::read_optional(path)
+    }
+
+    fn write(&self, path: &Path, contents: &str) -> Result<(), Error> {
+        fs::write(path, contents)
+    }
+
+    fn replace(&self, path: &Path, contents: &str) -> Result<(), Error> {
+        fs::replace(path, contents)
+    }
+
+    fn append(&self, path: &Path, contents: &str) -> Result<(), Error> {
+        fs::append(path, contents)
+    }
+
+    fn create_dir_all(&self, path: &Path) -> Result<(), Error> {
+        fs::create_dir_all(path)
+    }
+
+    fn remove_file(&self, path: &Path) -> Result<(), Error> {
+        fs::remove_file(path)
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        path.exists()
+    }
+
+    fn is_dir(&self, path: &Path) -> bool {
+        path.is_dir()
+    }
+
+    fn list(&self, dir: &Path) -> Result<Vec<PathBuf>, Error> {
+        let mut out = fs::read_dir(dir)?
+            .flatten()
+            .map(|a| a.path())
+            .collect::<Vec<_>>();
+        out.sort();
+        Ok(out)
+    }
+
+    fn modified(&self, path: &Path) -> Option<SystemTime> {
+        path.metadata().and_then(|a| a.modified()).ok()
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Entry {
+    Dir,
+    File {
+        contents: String,
+        modified: SystemTime,
+    },
+}
+
+/// Files kept in memory, so commands can be tested without touching the disk.
+///
+/// It fails where the disk would, e.g. writing into a directory that doesn't exist or appending
+/// to a missing file, with the same `io::ErrorKind`s.
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    entries: Mutex<BTreeMap<PathBuf, Entry>>,
+}
+
+fn io_error(path: &Path, kind: ErrorKind) -> Error {
+    Error::filesystem(path, kind.into())
+}
+
+impl MemoryStore {
+    pub fn new() -> MemoryStore {
+        MemoryStore::default()
+    }
+
+    fn entries(&self) -> std::sync::MutexGuard<'_, BTreeMap<PathBuf, Entry>> {
+        // a panic while holding the lock can't leave the map half updated
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Fails unless the directory `path` would be written into exists.
+    fn check_parent(entries: &BTreeMap<PathBuf, Entry>, path: &Path) -> Result<(), Error> {
+        match path.parent() {
+            Some(a) if a.parent().is_some() && !a.as_os_str().is_empty() => match entries.get(a) {
+                Some(Entry::Dir) => Ok(()),
+                Some(Entry::File { .. }) => Err(io_error(path, ErrorKind::NotADirectory)),
+                None => Err(io_error(path, ErrorKind::NotFound)),
+            },
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Store for MemoryStore {
+    fn read(&self, path: &Path) -> Result<String, Error> {
+        match self.entries().get(path) {
+            Some(Entry::File { contents, .. }) => Ok(contents.clone()),
+            Some(Entry::Dir) => Err(io_error(path, ErrorKind::IsADirectory)),
+            None => Err(io_error(path, ErrorKind::NotFound)),
+        }
+    }
+
+    fn write(&self, path: &Path, contents: &str) -> Result<(), Error> {
+        let mut entries = self.entries();
+        MemoryStore::check_parent(&entries, path)?;
+        if let Some(Entry::Dir) = entries.get(path) {
+            return Err(io_error(path, ErrorKind::IsADirectory));
+        }
+        entries.insert(
+            path.to_owned(),
+            Entry::File {
+                contents: contents.to_owned(),
+                modified: SystemTime::now(),
+            },
+        );
+        Ok(())
+    }
+
+    fn append(&self, path: &Path, contents: &str) -> Result<(), Error> {
+        match self.entries().get_mut(path) {
+            Some(Entry::File {
+                contents: old,
+                modified,
+            }) => {
+                old.push_str(contents);
+                *modified = SystemTime::now();
+                Ok(())
+            }
+            Some(Entry::Dir) => Err(io_error(path, ErrorKind::IsADirectory)),
+            None => Err(io_error(path, ErrorKind::NotFound)),
+        }
+    }
+
+    fn create_dir_all(&self, path: &Path) -> Result<(), Error> {
+        let mut entries = self.entries();
+        let dirs = path
+            .ancestors()
+            .filter(|a| a.parent().is_some() && !a.as_os_str().is_empty())
+            .collect::<Vec<_>>();
+        if let Some(a) = dirs
+            .iter()
+            .find(|a| matches!(entries.get(**a), Some(Entry::File { .. })))
+        {
+            return Err(io_error(a, ErrorKind::NotADirectory));
+        }
+        for a in dirs {
+            entries.insert(a.to_owned(), Entry::Dir);
+        }
+        Ok(())
+    }
+
+    fn remove_file(&self, path: &Path) -> Result<(), Error> {
+        let mut entries = self.entries();
+        match entries.get(path) {
+            Some(Entry::File { .. }) => {
+                entries.remove(path);
+                Ok(())
+            }
+            Some(Entry::Dir) => Err(io_error(path, ErrorKind::IsADirectory)),
+            None => Err(io_error(path, ErrorKind::NotFound)),
+        }
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        self.entries().contains_key(path)
+    }
+
+    fn is_dir(&self, path: &Path) -> bool {
+        matches!(self.entries().get(path), Some(Entry::Dir))
+    }
+
+    fn list(&self, dir: &Path) -> Result<Vec<PathBuf>, Error> {
+        let entries = self.entries();
+        match entries.get(dir) {
+            Some(Entry::Dir) => Ok(entries
+                .keys()
+                .filter(|a| a.parent() == Some(dir))
+                .cloned()
+                .collect()),
+            Some(Entry::File { .. }) => Err(io_error(dir, ErrorKind::NotADirectory)),
+            None => Err(io_error(dir, ErrorKind::NotFound)),
+        }
+    }
+
+    fn modified(&self, path: &Path) -> Option<SystemTime> {
+        match self.entries().get(path) {
+            Some(Entry::File { modified, .. }) => Some(*modified),
+            _ => None,
+        }
+    }
+}
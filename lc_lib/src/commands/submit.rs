@@ -1 +1,331 @@
+// sending a solution to LeetCode to be judged
+use anyhow::Result;
+use regex::Regex;
+use reqwest::Url;
+use std::time::Duration;
 
+use super::cache::ensure_online;
+use super::common::{Stage, GQL_ENDPOINT, LEETCODE_HOST};
+use super::error::Error;
+use super::info::{load, LastSubmission, Meta};
+use super::new::{generate_request_client, get_title_slug, query_endpoint, sanitize_lc_link};
+use super::repo::Repo;
+use super::request::send_json;
+
+/// How long to wait between asking LeetCode whether a judgement is ready.
+const CHECK_INTERVAL: Duration = Duration::from_secs(1);
+const CHECK_ATTEMPTS: usize = 60;
+
+/// The verdict LeetCode gave for a run or a submission.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Judgement {
+    /// e.g. `Accepted`, `Wrong Answer`, `Compile Error`
+    pub status: String,
+    /// Whether every case gave the expected answer
+    pub passed: bool,
+    pub runtime: Option<String>,
+    pub memory: Option<String>,
+    pub total_correct: Option<usize>,
+    pub total_testcases: Option<usize>,
+    /// Compile or runtime error message
+    pub error: Option<String>,
+    /// The inputs that were run, one case per entry
+    pub input: Vec<String>,
+    pub output: Vec<String>,
+    pub expected: Vec<String>,
+}
+
+impl Judgement {
+    /// Parses the response of the `check` endpoint once the judgement is finished.
+    /// `interpret` responses answer every example case, submissions only the first failing one.
+    pub fn from_json(json: &serde_json::Value, interpret: bool) -> Judgement {
+        let string = |key: &str| {
+            json[key]
+                .as_str()
+                .filter(|a| !a.is_empty())
+                .map(str::to_owned)
+        };
+        let list = |key: &str| match &json[key] {
+            serde_json::Value::Array(a) => a
+                .iter()
+                .map(|a| a.as_str().unwrap_or_default().to_owned())
+                .collect(),
+            serde_json::Value::String(a) if !a.is_empty() => vec![a.to_owned()],
+            _ => vec![],
+        };
+        let passed = if interpret {
+            json["run_success"].as_bool().unwrap_or(false)
+                && json["correct_answer"].as_bool().unwrap_or(false)
+        } else {
+            json["status_code"].as_u64() == Some(10)
+        };
+        Judgement {
+            status: string("status_msg").unwrap_or("Unknown".to_owned()),
+            passed,
+            runtime: string("status_runtime"),
+            memory: string("status_memory"),
+            total_correct: json["total_correct"].as_u64().map(|a| a as usize),
+            total_testcases: json["total_testcases"].as_u64().map(|a| a as usize),
+            error: string("full_compile_error")
+                .or(string("compile_error"))
+                .or(string("full_runtime_error"))
+                .or(string("runtime_error")),
+            input: match string("last_testcase") {
+                Some(a) => vec![a],
+                None => vec![],
+            },
+            output: list(if interpret {
+                "code_answer"
+            } else {
+                "code_output"
+            }),
+            expected: list(if interpret {
+                "expected_code_answer"
+            } else {
+                "expected_output"
+            }),
+        }
+    }
+}
+
+impl std::fmt::Display for Judgement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.status)?;
+        if let (Some(correct), Some(total)) = (self.total_correct, self.total_testcases) {
+            write!(f, " ({correct}/{total} cases)")?;
+        }
+        if let Some(runtime) = &self.runtime {
+            write!(f, ", {runtime}")?;
+        }
+        if let Some(memory) = &self.memory {
+            write!(f, ", {memory}")?;
+        }
+        if let Some(error) = &self.error {
+            write!(f, "\n{error}")?;
+        }
+        if !self.passed {
+            for (i, input) in self.input.iter().enumerate() {
+                write!(f, "\nInput:    {}", input.replace('\n', ", "))?;
+                if let Some(a) = self.output.get(i) {
+                    write!(f, "\nOutput:   {a}")?;
+                }
+                if let Some(a) = self.expected.get(i) {
+                    write!(f, "\nExpected: {a}")?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A tracked problem's solution, ready to be sent to LeetCode.
+#[derive(Debug, Clone)]
+pub struct Solution {
+    pub link: Url,
+    pub title_slug: String,
+    pub question_id: usize,
+    pub code: String,
+}
+
+/// Reads the solution of problem `num` and builds a client that is logged in to LeetCode.
+pub async fn prepare(repo: &Repo, num: usize) -> Result<(reqwest::Client, Solution)> {
+    let info = load(repo, num)?;
+    let link = match &info.meta.title_slug {
+        Some(slug) => sanitize_lc_link(&format!("https://{LEETCODE_HOST}/problems/{slug}/"))?,
+        None => sanitize_lc_link(&info.link)?,
+    };
+    let title_slug = get_title_slug(&link)?;
+    let client = generate_request_client(&link)?;
+
+    let question_id = match info.meta.question_id {
+        Some(a) => a,
+        None => {
+            let query = serde_json::json!({"query":"\n    query questionTitle($titleSlug: String!) {\n  question(titleSlug: $titleSlug) {\n    questionId\n  }\n}\n    ","variables":{"titleSlug":title_slug},"operationName":"questionTitle"});
+            let data = query_endpoint(&GQL_ENDPOINT.to_string(), &query, &client).await?;
+            match data["data"]["question"]["questionId"].as_str() {
+                Some(a) => a.parse::<usize>()?,
+                None => return Err(Error::parse("Could not get the questionId from JSON").into()),
+            }
+        }
+    };
+
+    let source = repo.read(&repo.main_rs(num))?;
+    Ok((
+        client,
+        Solution {
+            link,
+            title_slug,
+            question_id,
+            code: solution_code(&source),
+        },
+    ))
+}
+
+/// Strips the parts of `main.rs` that only exist to run the solution locally: the
+/// `struct Solution;` declaration, `fn main`, and any `#[cfg(test)]` module.
+pub fn solution_code(source: &str) -> String {
+    let mut code = Regex::new(r"(?m)^\s*struct Solution;\s*$\n?")
+        .unwrap()
+        .replace_all(source, "")
+        .into_owned();
+    for re in [r"(?m)^fn main\s*\(\s*\)", r"(?m)^#\[cfg\(test\)\]"] {
+        while let Some(found) = Regex::new(re).unwrap().find(&code) {
+            let start = found.start();
+            let end = match code[start..].find('{') {
+                Some(open) => matching_brace(&code, start + open).unwrap_or(code.len()),
+                None => code.len(),
+            };
+            code.replace_range(start..end, "");
+        }
+    }
+    code.trim().to_owned()
+}
+
+/// Returns the index just past the brace that closes the one at `open`, skipping braces inside
+/// comments and string or char literals.
+fn matching_brace(code: &str, open: usize) -> Option<usize> {
+    let s = &code[open..];
+    let b = s.as_bytes();
+    let is_ident = |i: usize| b[i].is_ascii_alphanumeric() || b[i] == b'_';
+    // every delimiter is ASCII, so indexing bytes never lands inside a multi-byte char
+    let find = |from: usize, pat: &str| s.get(from..)?.find(pat).map(|a| from + a);
+    let mut depth = 0;
+    let mut i = 0;
+    while i < b.len() {
+        match b[i] {
+            b'{' => depth += 1,
+            b'}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(open + i + 1);
+                }
+            }
+            b'/' if b.get(i + 1) == Some(&b'/') => i = find(i, "\n")?,
+            b'/' if b.get(i + 1) == Some(&b'*') => {
+                // block comments nest
+                let mut nested = 0;
+                loop {
+                    match (b.get(i)?, b.get(i + 1)) {
+                        (b'/', Some(b'*')) => nested += 1,
+                        (b'*', Some(b'/')) => nested -= 1,
+                        _ => {
+                            i += 1;
+                            continue;
+                        }
+                    }
+                    i += 2;
+                    if nested == 0 {
+                        break;
+                    }
+                }
+                continue;
+            }
+            b'"' => {
+                i += 1;
+                while *b.get(i)? != b'"' {
+                    i += if b[i] == b'\\' { 2 } else { 1 };
+                }
+            }
+            // raw strings, `r"..."`, `r#"..."#` and `br"..."`
+            b'r' if (i == 0
+                || !is_ident(i - 1)
+                || (b[i - 1] == b'b' && (i == 1 || !is_ident(i - 2))))
+                && matches!(b.get(i + 1), Some(b'"' | b'#')) =>
+            {
+                let hashes = s[i + 1..].bytes().take_while(|a| *a == b'#').count();
+                if b.get(i + 1 + hashes) == Some(&b'"') {
+                    let close = format!("\"{}", "#".repeat(hashes));
+                    i = find(i + 2 + hashes, &close)? + close.len() - 1;
+                }
+            }
+            // a char literal, unless it is a lifetime like `'a`
+            b'\'' => {
+                if b.get(i + 1) == Some(&b'\\') {
+                    i = find(i + 3, "'")?;
+                } else if let Some(c) = s[i + 1..].chars().next() {
+                    if b.get(i + 1 + c.len_utf8()) == Some(&b'\'') {
+                        i += 1 + c.len_utf8();
+                    }
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Polls LeetCode until the run or submission with `id` has been judged, calling `progress`
+/// with each state LeetCode reports along the way.
+pub async fn wait_for_judgement(
+    client: &reqwest::Client,
+    id: &str,
+    interpret: bool,
+    progress: impl Fn(Stage),
+) -> Result<Judgement> {
+    let url = format!("https://{LEETCODE_HOST}/submissions/detail/{id}/check/");
+    for _ in 0..CHECK_ATTEMPTS {
+        let json = send_json(client.get(&url), true).await?;
+        match json["state"].as_str() {
+            Some("SUCCESS") => return Ok(Judgement::from_json(&json, interpret)),
+            Some("STARTED") => progress(Stage::Running),
+            _ => progress(Stage::Queued),
+        }
+        tokio::time::sleep(CHECK_INTERVAL).await;
+    }
+    Err(Error::network(format!("Timed out waiting for LeetCode to judge {id}")).into())
+}
+
+/// Submits the solution of problem `num` and waits for the verdict.
+pub async fn submit(repo: &Repo, num: usize) -> Result<Judgement> {
+    submit_with_progress(repo, num, |_| {}).await
+}
+
+/// Like [`submit`], calling `progress` while the submission waits to be judged.
+pub async fn submit_with_progress(
+    repo: &Repo,
+    num: usize,
+    progress: impl Fn(Stage),
+) -> Result<Judgement> {
+    ensure_online("submitting")?;
+    let (client, sol) = prepare(repo, num).await?;
+    // a submission that timed out may still have been judged, so only rate limits are retried
+    let resp = send_json(
+        client
+            .post(format!(
+                "https://{LEETCODE_HOST}/problems/{}/submit/",
+                sol.title_slug
+            ))
+            .json(&serde_json::json!({
+                "lang": "rust",
+                "question_id": sol.question_id.to_string(),
+                "typed_code": sol.code,
+            })),
+        false,
+    )
+    .await?;
+    let id = match &resp["submission_id"] {
+        serde_json::Value::Number(a) => a.to_string(),
+        serde_json::Value::String(a) => a.to_owned(),
+        _ => {
+            return Err(
+                Error::network(format!("LeetCode did not accept the submission: {resp}")).into(),
+            )
+        }
+    };
+    let judgement = wait_for_judgement(&client, &id, false, progress).await?;
+    let mut meta = Meta::read(repo, num)?;
+    meta.last_submission = Some(LastSubmission {
+        status: judgement.status.clone(),
+        runtime: judgement.runtime.clone(),
+        submitted: chrono::Utc::now(),
+    });
+    meta.write(repo, num)?;
+    Ok(judgement)
+}
+
+pub async fn run(repo: &Repo, num: &usize) -> Result<()> {
+    let judgement = submit(repo, *num).await?;
+    println!("{judgement}");
+    Ok(())
+}
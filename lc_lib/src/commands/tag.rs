@@ -1,12 +1,16 @@
 use anyhow::Result;
-use clap::{Parser, ValueEnum};
-use regex::Regex;
-use std::io::Write;
+use clap::{Parser, Subcommand};
+use std::io::IsTerminal;
 use strum::{EnumIter, EnumString, IntoEnumIterator, IntoStaticStr};
 
-use super::common::{get_lc_dir, prompt_for_input};
+use super::common::{
+    arg_or_prompt, args_or_prompt, open_in_editor, prompt_for_input, suggest, tracked_problems,
+    ProblemSet,
+};
+use super::fs;
+use super::repo::Repo;
 
-#[derive(Debug, Clone, Parser, EnumString, IntoStaticStr, EnumIter)]
+#[derive(Debug, Clone, PartialEq, Eq, Parser, EnumString, IntoStaticStr, EnumIter)]
 pub enum TagType {
     // #[strum(serialize = "stack", serialize = "<other name>")]
     #[strum(serialize = "stack", ascii_case_insensitive)]
@@ -223,39 +227,36 @@ pub enum TagType {
     BiconnectedComponent,
 }
 
-#[derive(Debug, Clone, Parser)]
+#[derive(Debug, Clone, Subcommand)]
 pub enum TagCommand {
-    /// Add a tag to an existing problem
-    Add,
-    /// Remove a tag from an existing problem
-    Remove,
-    /// Edit the tags of a specified problem
-    Edit,
-    /// Search for all problems with a specific tag
-    Search,
+    /// Add tags to existing problems
+    Add {
+        /// Problems to tag, e.g. `42`, `1..20`, `1..=20` or `1,4,7`
+        problems: Option<ProblemSet>,
+        /// Tags to add to every selected problem
+        tags: Vec<TagType>,
+    },
+    /// Remove tags from existing problems
+    Remove {
+        /// Problems to untag, e.g. `42`, `1..20`, `1..=20` or `1,4,7`
+        problems: Option<ProblemSet>,
+        /// Tags to remove from every selected problem
+        tags: Vec<TagType>,
+    },
+    /// Edit the tags of a specified problem in `$EDITOR`
+    Edit {
+        /// Problem to edit the tags of
+        num: Option<usize>,
+    },
+    /// Search for all problems that have every one of the given tags
+    Search {
+        /// Tags to search for
+        tags: Vec<TagType>,
+    },
     /// List all the available tags for usage
     List,
-}
-
-impl ValueEnum for TagCommand {
-    fn to_possible_value(&self) -> Option<clap::builder::PossibleValue> {
-        Some(match self {
-            Self::Add => clap::builder::PossibleValue::new("add"),
-            Self::Remove => clap::builder::PossibleValue::new("remove"),
-            Self::Edit => clap::builder::PossibleValue::new("edit"),
-            Self::Search => clap::builder::PossibleValue::new("search"),
-            Self::List => clap::builder::PossibleValue::new("list"),
-        })
-    }
-    fn value_variants<'a>() -> &'a [Self] {
-        &[
-            Self::Add,
-            Self::Remove,
-            Self::Edit,
-            Self::Search,
-            Self::List,
-        ]
-    }
+    /// Rewrite every TAGS file in the repo into the canonical one tag per line format
+    Migrate,
 }
 
 impl std::fmt::Display for TagType {
@@ -264,115 +265,403 @@ impl std::fmt::Display for TagType {
     }
 }
 
-pub fn tag_subcommands(cmd: &TagCommand) -> Result<()> {
+pub fn tag_subcommands(repo: &Repo, cmd: &TagCommand) -> Result<()> {
     match cmd {
-        TagCommand::Add => {
-            let lc_dir = get_lc_dir()?;
-            let (_input_tag, tag) = prompt_for_input::<TagType>("Enter Tag to add: ")?;
-
-            let (_input_num, num) =
-                prompt_for_input::<usize>("Enter Problem Number to add Tag to: ")?;
-
-            let problem_dir = format!("{}{}{}", lc_dir, "src/", num);
-            let tag_path = format!("{}{}", problem_dir, "/TAGS");
-            let tag_file = std::fs::read_to_string(tag_path.clone())?;
-
-            // search for that tag in the problem, if it already exists, return an error of that
-            let re = Regex::new(format!(r"{}", tag).as_str()).unwrap();
-            if let Some(_a) = re.captures(tag_file.as_str()) {
-                return Err(anyhow::Error::msg(format!(
-                    "Tag `{tag}` already exists for this problem!"
-                )));
-            } else {
-                let mut file = std::fs::OpenOptions::new().append(true).open(tag_path)?;
-                writeln!(file, "{}", tag.to_string())?;
+        TagCommand::Add { problems, tags } => {
+            let problems = arg_or_prompt(problems.clone(), "Enter Problem(s) to add Tag to: ")?;
+            let tags = args_or_prompt(tags, "Enter Tag(s) to add: ")?;
+
+            for num in problems.resolve(repo)? {
+                for tag in tags.iter() {
+                    if add_tag(repo, num, tag)? {
+                        println!("Tag: {tag:?} was added to Problem: {num}");
+                    } else {
+                        println!("Tag: {tag:?} already exists for Problem: {num}");
+                    }
+                }
             }
-
-            println!("Tag: {tag:?} was added to Problem: {num}");
             Ok(())
         }
-        TagCommand::Remove => {
-            let lc_dir = get_lc_dir()?;
-
-            let (_input_num, num) =
-                prompt_for_input::<usize>("Enter Problem Number to remove a Tag from: ")?;
-
-            let prompt = format!("Enter Tag to remove from Problem {}: ", num);
-            let (_input_tag, tag) = prompt_for_input::<TagType>(prompt.as_str())?;
-
-            let problem_dir = format!("{}{}{}", lc_dir, "src/", num);
-            let tag_path = format!("{}{}", problem_dir, "/TAGS");
-            let tag_file = std::fs::read_to_string(tag_path.clone())?;
-
-            // file contents without the tag that is to be removed
-            let mut buf = Vec::new();
-            for line in tag_file.lines() {
-                if line.to_string() != tag.to_string() {
-                    buf.push(line);
+        TagCommand::Remove { problems, tags } => {
+            let problems =
+                arg_or_prompt(problems.clone(), "Enter Problem(s) to remove a Tag from: ")?;
+            let tags = args_or_prompt(tags, "Enter Tag(s) to remove: ")?;
+
+            for num in problems.resolve(repo)? {
+                for tag in tags.iter() {
+                    if remove_tag(repo, num, tag)? {
+                        println!("Tag: {tag:?} was removed from Problem: {num}");
+                    } else {
+                        println!("Tag: {tag:?} doesn't exist for Problem: {num}");
+                    }
                 }
             }
-            let buf = buf.join("\n");
-
-            let re = Regex::new(format!(r"{}", tag).as_str()).unwrap();
-
-            if let Some(_a) = re.captures(tag_file.as_str()) {
-                let mut file = std::fs::OpenOptions::new()
-                    .write(true)
-                    .truncate(true)
-                    .open(tag_path)?;
-                writeln!(file, "{}", buf)?;
-            } else {
-                return Err(anyhow::Error::msg(format!(
-                    "Tag `{tag}` doesn't exist for this problem!"
-                )));
-            }
-            println!("Tag: {tag:?} was removed from Problem: {num}");
             Ok(())
         }
-        TagCommand::Edit => {
-            // should just take a number and then give a list of all tags for that problem that
-            // the user can adjust
-            println!("Under Construction!");
+        TagCommand::Edit { num } => {
+            let num = arg_or_prompt(*num, "Enter Problem Number to edit the Tags of: ")?;
+            edit_tags(repo, num)
+        }
+        TagCommand::Search { tags } => {
+            let tags = args_or_prompt(tags, "Enter the tag(s) to search for: ")?;
+            let tags_str = tags
+                .iter()
+                .map(|a| a.to_string())
+                .collect::<Vec<_>>()
+                .join(", ");
+            for num in search_tags(repo, &tags)? {
+                println!("Problems with {}: {}", tags_str, num);
+            }
             Ok(())
         }
-        TagCommand::Search => {
-            // given a tag, finds all problems that have that tag
-            let (_input_tag, tag) = prompt_for_input::<TagType>("Enter the tag to search for: ")?;
-
-            // read all TAGS files in the system to find those with said tag
-            let mut out = Vec::new();
-            let lc_dir = get_lc_dir()?;
-            let paths = std::fs::read_dir(format!("{}{}", lc_dir, "src/"))?;
-            paths.into_iter().for_each(|path| {
-                if let Ok(path) = path {
-                    let tag_file = format!("{}{}", path.path().display(), "/TAGS");
-                    if let Ok(file) = std::fs::read_to_string(tag_file.clone()) {
-                        // find the tag within the file
-                        let re = Regex::new(format!(r"{}", tag).as_str()).unwrap();
-                        match re.captures(file.as_str()) {
-                            Some(_a) => out.push(path.file_name()),
-                            None => {}
-                        }
-                    } else {
-                        // this should be put into an error log
-                        // println!("Couldn't find the TAGS file in {:?}", path.file_name());
-                    }
+        TagCommand::List => list_tags(repo),
+        TagCommand::Migrate => {
+            let report = migrate_tags(repo)?;
+            for prob in report.iter() {
+                if prob.changed {
+                    println!("Normalized the TAGS of Problem: {}", prob.num);
                 }
-            });
-            out.iter().for_each(|path| {
-                println!("Problems with {}: {}", tag, path.to_str().unwrap_or(""))
-            });
+                for tag in prob.unknown.iter() {
+                    println!("Unknown tag `{}` kept in Problem: {}", tag, prob.num);
+                }
+            }
+            println!(
+                "Migrated {} of {} TAGS files",
+                report.iter().filter(|a| a.changed).count(),
+                report.len()
+            );
             Ok(())
         }
-        TagCommand::List => {
-            list_tags();
-            Ok(())
+    }
+}
+
+/// The parsed contents of a problem's TAGS file.
+///
+/// The file holds one canonical tag per line: the full name of a `TagType` (`DynamicProgramming`)
+/// or the spelling of a custom tag from `CUSTOM_TAGS`. Entries are matched exactly, so `Tree` never
+/// matches `BinaryTree`. Anything that isn't a known tag is kept as is so it can be reported
+/// instead of silently dropped.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Tags {
+    tags: Vec<String>,
+}
+
+impl Tags {
+    /// Parses the contents of a TAGS file, canonicalising every known tag and skipping blank
+    /// lines, comments and duplicates.
+    pub fn parse(contents: &str, custom: &[String]) -> Tags {
+        let mut out = Tags::default();
+        for line in contents.lines() {
+            let entry = match line.split_once('#') {
+                Some((a, _)) => a.trim(),
+                None => line.trim(),
+            };
+            if entry.is_empty() {
+                continue;
+            }
+            out.insert(canonical_tag(entry, custom).unwrap_or_else(|| entry.to_owned()));
+        }
+        out
+    }
+
+    /// Reads the TAGS file of problem `num`.
+    pub fn read(repo: &Repo, num: usize) -> Result<Tags> {
+        let file = repo.read(&repo.tags(num))?;
+        Ok(Tags::parse(&file, &custom_tags(repo)?))
+    }
+
+    /// Replaces the TAGS file of problem `num`. The new contents are written next to the old
+    /// file and renamed over it so a failed write never leaves a partial file behind.
+    pub fn write(&self, repo: &Repo, num: usize) -> Result<()> {
+        repo.replace(&repo.tags(num), &self.to_string())?;
+        Ok(())
+    }
+
+    pub fn contains(&self, tag: &str) -> bool {
+        self.tags.iter().any(|a| a == tag)
+    }
+
+    /// Returns `false` when the tag was already present.
+    pub fn insert(&mut self, tag: String) -> bool {
+        if self.contains(&tag) {
+            return false;
+        }
+        self.tags.push(tag);
+        true
+    }
+
+    /// Returns `false` when the tag wasn't present.
+    pub fn remove(&mut self, tag: &str) -> bool {
+        let len = self.tags.len();
+        self.tags.retain(|a| a != tag);
+        len != self.tags.len()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &String> {
+        self.tags.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty()
+    }
+
+    /// Entries that are neither a `TagType` nor a custom tag.
+    pub fn unknown<'a>(&'a self, custom: &'a [String]) -> impl Iterator<Item = &'a String> {
+        self.tags
+            .iter()
+            .filter(|a| canonical_tag(a, custom).is_none())
+    }
+}
+
+impl std::fmt::Display for Tags {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.tags.iter().try_for_each(|tag| writeln!(f, "{tag}"))
+    }
+}
+
+/// Adds `tag` to the TAGS file of problem `num`.
+/// Returns `false` when the problem already had the tag.
+pub fn add_tag(repo: &Repo, num: usize, tag: &TagType) -> Result<bool> {
+    let mut tags = Tags::read(repo, num)?;
+    if !tags.insert(tag.to_string()) {
+        return Ok(false);
+    }
+    tags.write(repo, num)?;
+    Ok(true)
+}
+
+/// Removes `tag` from the TAGS file of problem `num`.
+/// Returns `false` when the problem didn't have the tag.
+pub fn remove_tag(repo: &Repo, num: usize, tag: &TagType) -> Result<bool> {
+    let mut tags = Tags::read(repo, num)?;
+    if !tags.remove(&tag.to_string()) {
+        return Ok(false);
+    }
+    tags.write(repo, num)?;
+    Ok(true)
+}
+
+/// Finds every tracked problem that has all of `tags`.
+pub fn search_tags(repo: &Repo, tags: &[TagType]) -> Result<Vec<usize>> {
+    // read all TAGS files in the system to find those with said tags
+    let mut out = Vec::new();
+    for num in tracked_problems(repo)? {
+        if let Ok(file) = Tags::read(repo, num) {
+            if tags.iter().all(|tag| file.contains(&tag.to_string())) {
+                out.push(num);
+            }
+        } else {
+            // this should be put into an error log
+            // println!("Couldn't find the TAGS file in {:?}", num);
         }
     }
+    Ok(out)
 }
 
-pub fn list_tags() {
+/// What `migrate_tags` did to a single problem's TAGS file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TagMigration {
+    pub num: usize,
+    /// Whether the file was rewritten
+    pub changed: bool,
+    /// Entries that couldn't be matched to a known tag and were kept as they were
+    pub unknown: Vec<String>,
+}
+
+/// Rewrites every TAGS file in the repo in the canonical format: full tag names, one per line,
+/// with duplicates and blank lines removed.
+pub fn migrate_tags(repo: &Repo) -> Result<Vec<TagMigration>> {
+    let custom = custom_tags(repo)?;
+    let mut out = Vec::new();
+    for num in tracked_problems(repo)? {
+        let Some(raw) = repo.read_optional(&repo.tags(num))? else {
+            continue;
+        };
+        let tags = Tags::parse(&raw, &custom);
+        let changed = tags.to_string() != raw;
+        if changed {
+            tags.write(repo, num)?;
+        }
+        out.push(TagMigration {
+            num,
+            changed,
+            unknown: tags.unknown(&custom).cloned().collect(),
+        });
+    }
+    Ok(out)
+}
+
+pub fn list_tags(repo: &Repo) -> Result<()> {
     for tag in TagType::iter() {
         println!("Tag: {tag}");
     }
+    for tag in custom_tags(repo)? {
+        println!("Custom Tag: {tag}");
+    }
+    Ok(())
+}
+
+/// Reads the repo specific tags from `CUSTOM_TAGS` at the top of the repo, one per line.
+/// A missing file means there are no custom tags.
+pub fn custom_tags(repo: &Repo) -> Result<Vec<String>> {
+    let Some(file) = repo.read_optional(&repo.custom_tags())? else {
+        return Ok(Vec::new());
+    };
+    Ok(file
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_owned)
+        .collect())
+}
+
+impl TagType {
+    /// Parses either the short name used on the command line (`dp`) or the full name that is
+    /// written into TAGS files (`DynamicProgramming`), ignoring case.
+    pub fn from_name(name: &str) -> Option<TagType> {
+        name.parse::<TagType>()
+            .ok()
+            .or_else(|| TagType::iter().find(|a| a.to_string().eq_ignore_ascii_case(name)))
+    }
+
+    /// The short name used on the command line.
+    pub fn short_name(&self) -> &'static str {
+        self.clone().into()
+    }
+}
+
+/// Returns the canonical spelling of `entry` if it is a known or custom tag.
+pub fn canonical_tag(entry: &str, custom: &[String]) -> Option<String> {
+    match TagType::from_name(entry) {
+        Some(a) => Some(a.to_string()),
+        None => custom
+            .iter()
+            .find(|a| a.eq_ignore_ascii_case(entry))
+            .cloned(),
+    }
+}
+
+/// An entry from an edited tag list that isn't a known or custom tag.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownTag {
+    pub entry: String,
+    pub suggestion: Option<String>,
+}
+
+/// Parses a tag list as written in the editor: one tag per line with `#` starting a comment.
+/// Returns the canonical tags in order without duplicates, or every entry that is unknown.
+pub fn parse_tag_list(
+    contents: &str,
+    custom: &[String],
+) -> std::result::Result<Tags, Vec<UnknownTag>> {
+    let mut tags = Tags::default();
+    let mut unknown = Vec::new();
+    for line in contents.lines() {
+        let entry = match line.split_once('#') {
+            Some((a, _)) => a.trim(),
+            None => line.trim(),
+        };
+        if entry.is_empty() {
+            continue;
+        }
+        match canonical_tag(entry, custom) {
+            Some(tag) => {
+                tags.insert(tag);
+            }
+            None => {
+                let names = TagType::iter()
+                    .flat_map(|a| [a.short_name().to_owned(), a.to_string()])
+                    .chain(custom.iter().cloned())
+                    .collect::<Vec<_>>();
+                let suggestion = suggest(entry, names.iter().map(String::as_str))
+                    .and_then(|a| canonical_tag(a, custom));
+                unknown.push(UnknownTag {
+                    entry: entry.to_owned(),
+                    suggestion,
+                });
+            }
+        }
+    }
+    if unknown.is_empty() {
+        Ok(tags)
+    } else {
+        Err(unknown)
+    }
+}
+
+/// Opens the tags of problem `num` in the user's editor, with every available tag listed as a
+/// comment. Once the editor closes the entries are validated and the TAGS file is replaced in a
+/// single rename, so nothing changes unless every entry is a known or custom tag.
+pub fn edit_tags(repo: &Repo, num: usize) -> Result<()> {
+    let current = Tags::read(repo, num)?;
+    let custom = custom_tags(repo)?;
+
+    let mut template =
+        format!("# Tags for Problem {num}, one per line. Lines starting with `#` are ignored.\n");
+    current
+        .iter()
+        .for_each(|tag| template.push_str(&format!("{tag}\n")));
+    template.push_str("\n# Available tags:\n");
+    TagType::iter()
+        .for_each(|tag| template.push_str(&format!("#   {} ({})\n", tag, tag.short_name())));
+    if !custom.is_empty() {
+        template.push_str("#\n# Custom tags:\n");
+        custom
+            .iter()
+            .for_each(|tag| template.push_str(&format!("#   {tag}\n")));
+    }
+
+    let tmp = std::env::temp_dir().join(format!("lc_tags_{}_{}", num, std::process::id()));
+    fs::write(&tmp, template)?;
+    let edited = loop {
+        if let Err(e) = open_in_editor(&tmp) {
+            break Err(e);
+        }
+        let contents = fs::read_to_string(&tmp)?;
+        match parse_tag_list(&contents, &custom) {
+            Ok(tags) => break Ok(tags),
+            Err(unknown) => {
+                for tag in unknown.iter() {
+                    match &tag.suggestion {
+                        Some(a) => eprintln!("Unknown tag `{}`, did you mean `{a}`?", tag.entry),
+                        None => eprintln!("Unknown tag `{}`", tag.entry),
+                    }
+                }
+                let retry = std::io::stdin().is_terminal() && {
+                    let (answer, _) = prompt_for_input::<String>("Re-open the editor? [Y/n] ")?;
+                    !answer.to_ascii_lowercase().starts_with('n')
+                };
+                if !retry {
+                    break Err(anyhow::Error::msg(format!(
+                        "Tags for Problem {num} were left unchanged"
+                    )));
+                }
+            }
+        }
+    };
+    let _ = fs::remove_file(&tmp);
+    let tags = edited?;
+
+    let added = tags
+        .iter()
+        .filter(|a| !current.contains(a))
+        .collect::<Vec<_>>();
+    let removed = current
+        .iter()
+        .filter(|a| !tags.contains(a))
+        .collect::<Vec<_>>();
+    if added.is_empty() && removed.is_empty() {
+        println!("No changes to the Tags of Problem: {num}");
+        return Ok(());
+    }
+
+    tags.write(repo, num)?;
+
+    added
+        .iter()
+        .for_each(|tag| println!("Tag: {tag} was added to Problem: {num}"));
+    removed
+        .iter()
+        .for_each(|tag| println!("Tag: {tag} was removed from Problem: {num}"));
+    Ok(())
 }
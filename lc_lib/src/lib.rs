@@ -5,4 +5,1031 @@ pub use commands::*;
 mod tests {
     #[allow(unused_imports)]
     use super::*;
+
+    /// A problem in LeetCode's list with only its number and names set.
+    fn listed(id: usize, title: &str, slug: &str) -> browse::ListedProblem {
+        browse::ListedProblem {
+            question_id: id,
+            frontend_id: id,
+            title: title.to_owned(),
+            title_slug: slug.to_owned(),
+            difficulty: None,
+            paid_only: false,
+            topics: vec![],
+            status: None,
+        }
+    }
+
+    /// A tracked problem called `Title {number}`, with one tag per line of `tags`.
+    fn tracked(
+        number: usize,
+        difficulty: info::Difficulty,
+        status: info::Status,
+        tags: &str,
+    ) -> info::ProblemInfo {
+        info::ProblemInfo {
+            number,
+            title: format!("Title {number}"),
+            link: String::new(),
+            meta: info::Meta {
+                difficulty: Some(difficulty),
+                status,
+                ..Default::default()
+            },
+            tags: tag::Tags::parse(tags, &[]),
+        }
+    }
+
+    #[test]
+    fn problem_set_resolves_numbers_and_ranges() {
+        let tracked = [1, 2, 5, 19, 20, 42];
+        let set = "1..20".parse::<common::ProblemSet>().unwrap();
+        assert_eq!(set.resolve_from(&tracked).unwrap(), vec![1, 2, 5, 19]);
+        let set = "42,1..=20".parse::<common::ProblemSet>().unwrap();
+        assert_eq!(
+            set.resolve_from(&tracked).unwrap(),
+            vec![1, 2, 5, 19, 20, 42]
+        );
+        let set = "7".parse::<common::ProblemSet>().unwrap();
+        assert!(set.resolve_from(&tracked).is_err());
+        assert!("20..1".parse::<common::ProblemSet>().is_err());
+        assert!("dp".parse::<common::ProblemSet>().is_err());
+        assert!("1..=18446744073709551615"
+            .parse::<common::ProblemSet>()
+            .is_err());
+        assert!("1..x".parse::<common::ProblemSet>().is_err());
+    }
+
+    #[test]
+    fn tag_list_is_validated_and_canonicalised() {
+        let custom = vec!["Blind75".to_owned()];
+        let tags = tag::parse_tag_list("dp\nStack # comment\n\nblind75\nDP\n", &custom).unwrap();
+        assert_eq!(
+            tags.iter().collect::<Vec<_>>(),
+            vec!["DynamicProgramming", "Stack", "Blind75"]
+        );
+
+        let unknown = tag::parse_tag_list("stak\nnot-a-tag-at-all\n", &custom).unwrap_err();
+        assert_eq!(unknown[0].suggestion.as_deref(), Some("Stack"));
+        assert_eq!(unknown[1].suggestion, None);
+    }
+
+    #[test]
+    fn tags_match_exactly() {
+        let tags = tag::Tags::parse("BinaryTree\n\n  bst \nBinaryTree\nsomething\n", &[]);
+        assert!(tags.contains("BinaryTree"));
+        assert!(tags.contains("BinarySearchTree"));
+        assert!(!tags.contains("Tree"));
+        assert_eq!(tags.unknown(&[]).collect::<Vec<_>>(), vec!["something"]);
+        assert_eq!(
+            tags.to_string(),
+            "BinaryTree\nBinarySearchTree\nsomething\n"
+        );
+    }
+
+    #[test]
+    fn cargo_test_output_is_summarised() {
+        let out = local::TestSummary::parse(
+            "running 3 tests
+test tests::a ... ok
+test tests::b ... FAILED
+test tests::c ... ignored
+
+test result: FAILED. 1 passed; 1 failed; 1 ignored; 0 measured; 0 filtered out
+
+error: test failed, to rerun pass `--bin 42`",
+        );
+        assert_eq!((out.passed, out.failed, out.ignored), (1, 1, 1));
+        assert_eq!(out.failures, vec!["tests::b"]);
+        assert!(!out.success());
+        assert_eq!(out.compact(), "1 passed, 1 failed, 1 ignored (tests::b)");
+
+        let out = local::TestSummary::parse("error[E0308]: mismatched types\n");
+        assert_eq!(
+            out.build_error.as_deref(),
+            Some("error[E0308]: mismatched types")
+        );
+    }
+
+    #[test]
+    fn statement_html_becomes_markdown() {
+        let html = "<p>Given an array of integers <code>nums</code>&nbsp;and an integer <code>target</code>, return <em>indices</em>.</p>
+
+<p>&nbsp;</p>
+<p><strong class=\"example\">Example 1:</strong></p>
+
+<pre>
+<strong>Input:</strong> nums = [2,7,11,15], target = 9
+<strong>Output:</strong> [0,1]
+</pre>
+
+<p><strong>Constraints:</strong></p>
+
+<ul>
+\t<li><code>2 &lt;= nums.length &lt;= 10<sup>4</sup></code></li>
+\t<li>Only one valid answer exists.</li>
+</ul>
+";
+        assert_eq!(
+            new::html_to_markdown(html),
+            "Given an array of integers `nums` and an integer `target`, return *indices*.
+
+**Example 1:**
+
+```
+Input: nums = [2,7,11,15], target = 9
+Output: [0,1]
+```
+
+**Constraints:**
+
+- `2 <= nums.length <= 10<sup>4</sup>`
+- Only one valid answer exists."
+        );
+    }
+
+    #[test]
+    fn local_scaffolding_is_stripped_before_submitting() {
+        let source = "struct Solution;
+
+impl Solution {
+    pub fn two_sum(nums: Vec<i32>, target: i32) -> Vec<i32> {
+        if target > 0 { vec![0, 1] } else { vec![] }
+    }
+}
+
+fn main() {
+    let sol = Solution::two_sum(vec![1, 2], 3);
+    println!(\"{:?}\", sol);
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn works() {}
+}
+";
+        assert_eq!(
+            submit::solution_code(source),
+            "impl Solution {
+    pub fn two_sum(nums: Vec<i32>, target: i32) -> Vec<i32> {
+        if target > 0 { vec![0, 1] } else { vec![] }
+    }
+}"
+        );
+
+        // braces in literals and comments don't end `main` early
+        let source = "impl Solution {
+    pub fn brackets<'a>(s: &'a str) -> bool {
+        s.starts_with('{')
+    }
+}
+
+fn main() {
+    println!(\"{{ {:?}\", Solution::brackets(\"{\")); // }
+    let _ = ('}', r#\"}\"#, '\\'');
+    /* } /* nested } */ } */
+    let _ = \"\\\"}\";
+}
+";
+        assert_eq!(
+            submit::solution_code(source),
+            "impl Solution {
+    pub fn brackets<'a>(s: &'a str) -> bool {
+        s.starts_with('{')
+    }
+}"
+        );
+    }
+
+    #[test]
+    fn terminal_editors_are_told_apart() {
+        let editor = |a: &str| a.split(' ').map(str::to_owned).collect::<Vec<_>>();
+        assert!(common::is_terminal_editor(&editor("/usr/bin/nvim")));
+        assert!(common::is_terminal_editor(&editor("emacs -nw")));
+        assert!(!common::is_terminal_editor(&editor("emacs")));
+        assert!(!common::is_terminal_editor(&editor("code --wait")));
+    }
+
+    #[test]
+    fn stats_count_progress_and_streaks() {
+        use chrono::{Local, NaiveDate, TimeZone};
+        use info::{Difficulty, Status};
+
+        let day = |d: u32| NaiveDate::from_ymd_opt(2024, 3, d).unwrap();
+        let at = |d: u32, h: u32| {
+            Local
+                .from_local_datetime(&day(d).and_hms_opt(h, 0, 0).unwrap())
+                .unwrap()
+                .to_utc()
+        };
+        let problem = |number, difficulty, finished: Option<(u32, u32)>, tags: &str| {
+            let status = match finished {
+                Some(_) => Status::Finished,
+                None => Status::Attempted,
+            };
+            let mut prob = tracked(number, difficulty, status, tags);
+            prob.meta.created = Some(at(1, 0));
+            prob.meta.finished = finished.map(|(d, h)| at(d, h));
+            prob
+        };
+        let mut problems = [
+            problem(1, Difficulty::Easy, Some((1, 6)), "Array\nHashTable"),
+            problem(2, Difficulty::Easy, Some((2, 12)), "Array"),
+            problem(3, Difficulty::Medium, Some((5, 0)), ""),
+            problem(4, Difficulty::Medium, Some((6, 0)), ""),
+            problem(5, Difficulty::Hard, None, "Array"),
+        ];
+
+        problems[2].meta.dailies = vec![day(5)];
+        problems[3].meta.dailies = vec![day(6)];
+        // unfinished dailies don't count
+        problems[4].meta.dailies = vec![day(7)];
+
+        let stats = stats::Stats::compute(&problems, at(7, 12));
+        assert_eq!(stats.total, 5);
+        assert_eq!(stats.by_status["Finished"], 4);
+        assert_eq!(stats.by_status["Hidden"], 0);
+        assert_eq!(stats.by_difficulty["Easy"], 2);
+        assert_eq!(stats.by_tag["Array"], 3);
+        assert_eq!(stats.solved_per_month["2024-03"], 4);
+        assert_eq!(stats.solved_per_week["2024-W09"], 2);
+        assert_eq!(stats.solved_per_week["2024-W10"], 2);
+        assert_eq!((stats.current_streak, stats.longest_streak), (2, 2));
+        assert_eq!(
+            (stats.current_daily_streak, stats.longest_daily_streak),
+            (2, 2)
+        );
+        assert_eq!(stats.average_hours_to_finish["Easy"], 21.0);
+        assert_eq!(stats.average_hours_to_finish["Medium"], 108.0);
+
+        assert_eq!(stats::streaks(vec![day(1), day(2)], day(7)), (0, 2));
+    }
+
+    #[test]
+    fn reviews_follow_sm2() {
+        use chrono::NaiveDate;
+        use info::Difficulty;
+        use review::{derived_grade, Review};
+
+        let day = |d: u32| NaiveDate::from_ymd_opt(2024, 3, d).unwrap();
+        let mut review = Review::new(day(1));
+        assert_eq!(review.due, day(2));
+
+        review.grade(5, day(2));
+        assert_eq!((review.interval_days, review.due), (1, day(3)));
+        review.grade(4, day(3));
+        assert_eq!((review.interval_days, review.due), (6, day(9)));
+        // a 5 raises the ease by 0.1 and a 4 leaves it as is
+        assert!((review.ease - 2.6).abs() < 1e-9);
+        review.grade(3, day(9));
+        assert_eq!(review.interval_days, 16);
+        assert_eq!(review.repetitions, 3);
+
+        review.grade(1, day(25));
+        assert_eq!((review.repetitions, review.due), (0, day(26)));
+        assert!(review.ease >= 1.3);
+
+        assert_eq!(derived_grade(false, Some(5), Some(Difficulty::Easy)), 2);
+        assert_eq!(derived_grade(true, Some(10), Some(Difficulty::Easy)), 5);
+        assert_eq!(derived_grade(true, Some(50), Some(Difficulty::Medium)), 4);
+        assert_eq!(derived_grade(true, Some(200), Some(Difficulty::Hard)), 3);
+    }
+
+    #[test]
+    fn daily_challenge_is_parsed() {
+        let json = serde_json::json!({"data":{"activeDailyCodingChallengeQuestion":{"date":"2024-03-07","link":"/problems/middle-of-the-linked-list/","question":{"questionFrontendId":"876","title":"Middle of the Linked List","titleSlug":"middle-of-the-linked-list","difficulty":"Easy"}}}});
+        let daily = daily::Daily::from_json(&json).unwrap();
+        assert_eq!(daily.number, 876);
+        assert_eq!(
+            daily.date,
+            chrono::NaiveDate::from_ymd_opt(2024, 3, 7).unwrap()
+        );
+        assert_eq!(daily.difficulty, Some(info::Difficulty::Easy));
+        assert_eq!(
+            daily.link,
+            "https://leetcode.com/problems/middle-of-the-linked-list/"
+        );
+        assert!(daily::Daily::from_json(&serde_json::json!({"data":null})).is_err());
+    }
+
+    #[test]
+    fn problem_list_is_parsed_and_filtered() {
+        let json = serde_json::json!({"difficulty":"Hard","questionId":"42","frontendQuestionId":"42","paidOnly":false,"status":"ac","title":"Trapping Rain Water","titleSlug":"trapping-rain-water","topicTags":[{"name":"Array","slug":"array"},{"name":"Stack","slug":"stack"}]});
+        let prob = browse::ListedProblem::from_json(&json).unwrap();
+        assert_eq!(prob.frontend_id, 42);
+        assert_eq!(prob.difficulty, Some(info::Difficulty::Hard));
+        assert_eq!(prob.topics, vec!["array", "stack"]);
+        assert_eq!(prob.status, Some(browse::RemoteStatus::Solved));
+        assert_eq!(
+            prob.link(),
+            "https://leetcode.com/problems/trapping-rain-water/"
+        );
+
+        let filter = browse::BrowseFilter {
+            difficulty: Some(info::Difficulty::Medium),
+            tag: Some("stack".to_owned()),
+            status: Some(browse::RemoteStatus::Todo),
+            keyword: None,
+            exclude_premium: true,
+        };
+        assert_eq!(
+            filter.to_json(),
+            serde_json::json!({"difficulty":"MEDIUM","tags":["stack"],"status":"NOT_STARTED","premiumOnly":false})
+        );
+
+        use clap::Parser;
+        assert!(common::Cli::try_parse_from(["lc", "browse", "--per-page", "0"]).is_err());
+        assert!(common::Cli::try_parse_from(["lc", "browse", "--page", "0"]).is_err());
+        assert!(common::Cli::try_parse_from(["lc", "browse", "--per-page", "1"]).is_ok());
+    }
+
+    #[test]
+    fn catalog_merges_and_filters() {
+        let listed =
+            |id: usize, slug: &str, difficulty, paid_only, topics: &[&str]| browse::ListedProblem {
+                question_id: id + 1000,
+                difficulty: Some(difficulty),
+                paid_only,
+                topics: topics.iter().map(|a| a.to_string()).collect(),
+                status: Some(browse::RemoteStatus::Solved),
+                ..listed(id, &slug.replace('-', " "), slug)
+            };
+        use info::Difficulty::*;
+        let mut catalog = catalog::Catalog::default();
+        catalog.merge(vec![
+            listed(42, "trapping-rain-water", Hard, false, &["array", "stack"]),
+            listed(1, "two-sum", Easy, false, &["array"]),
+        ]);
+        catalog.merge(vec![
+            listed(1, "two-sum", Easy, false, &["array", "hash-table"]),
+            listed(156, "binary-tree-upside-down", Medium, true, &["tree"]),
+        ]);
+        assert_eq!(
+            catalog
+                .problems
+                .iter()
+                .map(|a| a.frontend_id)
+                .collect::<Vec<_>>(),
+            vec![1, 42, 156]
+        );
+        assert_eq!(catalog.by_number(1).unwrap().topics.len(), 2);
+        assert_eq!(catalog.by_slug("two-sum").unwrap().status, None);
+
+        let page = vec![
+            listed(1, "two-sum", Easy, false, &["array", "hash-table"]),
+            listed(42, "trapping-rain-water", Hard, false, &["array", "stack"]),
+        ];
+        assert!(catalog.unchanged(3, &page));
+        assert!(!catalog.unchanged(4, &page));
+        assert!(!catalog.unchanged(3, &page[1..]));
+        assert_eq!(catalog.clone().update_page(0, page), (0, 0));
+        let page = vec![
+            listed(42, "trapping-rain-water", Hard, true, &["array", "stack"]),
+            listed(156, "binary-tree-upside-down", Medium, true, &["tree"]),
+            listed(2, "add-two-numbers", Medium, false, &["linked-list"]),
+        ];
+        assert_eq!(catalog.update_page(1, page), (1, 1));
+        assert!(catalog.by_number(42).unwrap().paid_only);
+
+        let filter = browse::BrowseFilter {
+            tag: Some("array".to_owned()),
+            ..Default::default()
+        };
+        assert_eq!(catalog.filter(&filter).len(), 2);
+        let filter = browse::BrowseFilter {
+            keyword: Some("Rain".to_owned()),
+            ..Default::default()
+        };
+        assert_eq!(catalog.filter(&filter)[0].frontend_id, 42);
+        let filter = browse::BrowseFilter {
+            exclude_premium: true,
+            ..Default::default()
+        };
+        assert_eq!(catalog.filter(&filter).len(), 2);
+    }
+
+    #[test]
+    fn new_accepts_numbers_slugs_and_titles() {
+        let problems = [
+            listed(1, "Two Sum", "two-sum"),
+            listed(42, "Trapping Rain Water", "trapping-rain-water"),
+        ];
+        let candidates = problems.iter().collect::<Vec<_>>();
+        let find = |a: &str| new::find_problem(a, &candidates).map(|a| a.frontend_id);
+
+        assert_eq!(find("42").unwrap(), 42);
+        assert_eq!(find("trapping-rain-water").unwrap(), 42);
+        assert_eq!(find("trapping rain water").unwrap(), 42);
+        assert!(find("7").is_err());
+        let e = find("Traping Rain Water").unwrap_err().to_string();
+        assert!(e.contains("did you mean `Trapping Rain Water`"), "{e}");
+    }
+
+    #[test]
+    fn problem_lists_skip_comments() {
+        let repo = repo::Repo::at(std::env::temp_dir());
+        let path = repo
+            .root()
+            .join(format!("lc_problem_list_{}", std::process::id()));
+        std::fs::write(&path, "# weekly set\n42\n\ntwo-sum  # warm up\n").unwrap();
+        let problems = new::read_problem_list(&repo, &path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(problems, vec!["42", "two-sum"]);
+    }
+
+    #[test]
+    fn problem_links_are_parsed() {
+        use link::ProblemRef;
+        let canonical = "https://leetcode.com/problems/two-sum/";
+        for a in [
+            "https://leetcode.com/problems/two-sum",
+            "https://leetcode.com/problems/two-sum/description/",
+            "https://www.leetcode.com/problems/two-sum/?envType=daily-question#top",
+            "leetcode.com/problems/two-sum",
+            " https://leetcode.com/problems/two-sum/ ",
+        ] {
+            let prob = ProblemRef::parse(a).unwrap();
+            assert_eq!(prob.link().as_str(), canonical, "{a}");
+            assert_eq!(prob.contest, None);
+            assert_eq!(prob.submission, None);
+        }
+        let prob =
+            ProblemRef::parse("https://leetcode.com/contest/weekly-contest-360/problems/two-sum/")
+                .unwrap();
+        assert_eq!(prob.slug, "two-sum");
+        assert_eq!(prob.contest.as_deref(), Some("weekly-contest-360"));
+        let prob =
+            ProblemRef::parse("https://leetcode.com/problems/two-sum/submissions/1234/").unwrap();
+        assert_eq!(prob.submission, Some(1234));
+
+        let err = |a: &str| ProblemRef::parse(a).unwrap_err().to_string();
+        assert!(err("https://github.com/problems/two-sum/").contains("is not leetcode.com"));
+        assert!(err("https://leetcode.com/explore/learn/").contains("not the path of a problem"));
+        assert!(err("https://leetcode.com/contest/weekly-contest-360/").contains("contest"));
+        assert!(err("https://leetcode.com/submissions/detail/1/").contains("Submission links"));
+        assert!(err("https://leetcode.com/problems/").contains("missing the problem's slug"));
+        assert!(err("https://leetcode.com/problems/Two_Sum/").contains("not a problem slug"));
+        assert!(
+            err("https://leetcode.com/problems/two-sum/submissions/x/").contains("submission id")
+        );
+    }
+
+    #[test]
+    fn accepted_submissions_are_grouped_by_problem() {
+        let sub = |id: u64, slug: &str, lang: &str, status: &str, time: i64| {
+            import::Submission::from_json(&serde_json::json!({
+                "id": id.to_string(),
+                "statusDisplay": status,
+                "lang": lang,
+                "timestamp": time.to_string(),
+                "title": slug.to_uppercase(),
+                "titleSlug": slug,
+            }))
+            .unwrap()
+        };
+        // newest first, like LeetCode lists them
+        let submissions = [
+            sub(9, "two-sum", "rust", "Accepted", 900),
+            sub(8, "lru-cache", "python3", "Accepted", 800),
+            sub(7, "two-sum", "rust", "Wrong Answer", 700),
+            sub(6, "two-sum", "rust", "Accepted", 600),
+            sub(5, "add-two-numbers", "rust", "Accepted", 500),
+            sub(4, "two-sum", "python3", "Time Limit Exceeded", 400),
+        ];
+        assert!(!submissions[2].accepted);
+        assert_eq!(submissions[0].timestamp.timestamp(), 900);
+
+        let solved = import::solved(&submissions);
+        let slugs = solved
+            .iter()
+            .map(|a| a.title_slug.as_str())
+            .collect::<Vec<_>>();
+        // lru-cache was never accepted in Rust
+        assert_eq!(slugs, vec!["add-two-numbers", "two-sum"]);
+        let two_sum = &solved[1];
+        assert_eq!(two_sum.started.timestamp(), 400);
+        assert_eq!(two_sum.finished.timestamp(), 600);
+        assert_eq!(two_sum.last_accepted, 9);
+    }
+
+    #[test]
+    fn old_solutions_are_identified() {
+        use import::{identify, Identity};
+        use std::path::{Path, PathBuf};
+        let id = |path: &str, contents: &str| identify(Path::new(path), contents);
+        assert_eq!(id("old/0001-two-sum.rs", ""), Some(Identity::Number(1)));
+        assert_eq!(id("old/p42.rs", ""), Some(Identity::Number(42)));
+        assert_eq!(
+            id("problems/two_sum/mod.rs", ""),
+            Some(Identity::Slug("two-sum".to_owned()))
+        );
+        assert_eq!(
+            id("two_sum/src/main.rs", ""),
+            Some(Identity::Slug("two-sum".to_owned()))
+        );
+        // a numbered name wins over a numbered header, which is only taken with its title
+        assert_eq!(
+            id("0042-trapping-rain-water.rs", "// 1. sort the input\n"),
+            Some(Identity::Number(42))
+        );
+        let titled = id("misc/a.rs", "//! 146. LRU Cache\nstruct LRUCache;\n").unwrap();
+        assert_eq!(
+            titled,
+            Identity::Titled {
+                num: 146,
+                title: "LRU Cache".to_owned()
+            }
+        );
+        let catalog = catalog::Catalog {
+            synced: None,
+            problems: vec![listed(146, "LRU Cache", "lru-cache")],
+        };
+        assert!(import::resolve(&titled, &catalog).is_ok());
+        let note = id("notes.rs", "// 146. sort the input\n").unwrap();
+        assert!(import::resolve(&note, &catalog).is_err());
+        assert_eq!(
+            id(
+                "0001-x.rs",
+                "// Link: https://leetcode.com/problems/lru-cache/description/\n"
+            ),
+            Some(Identity::Slug("lru-cache".to_owned()))
+        );
+        assert_eq!(
+            id("old/123.rs", "fn main() {}\n// 7. Reverse\n"),
+            Some(Identity::Number(123))
+        );
+        assert_eq!(id("mod.rs", ""), None);
+
+        // helpers declared by a solution's `mod.rs` move with it instead of being problems
+        let file = |path: &str, contents: &str| (PathBuf::from(path), contents.to_owned());
+        let sources = import::group_modules(vec![
+            file("old/0001-two-sum.rs", "impl Solution {}"),
+            file("old/two_sum/helpers.rs", "pub fn f() {}"),
+            file("old/two_sum/mod.rs", "mod helpers;\nimpl Solution {}"),
+        ]);
+        assert_eq!(sources.len(), 2);
+        assert!(sources[0].modules.is_empty());
+        assert_eq!(sources[1].path, Path::new("old/two_sum/mod.rs"));
+        assert_eq!(
+            sources[1].modules,
+            vec![file("helpers.rs", "pub fn f() {}")]
+        );
+
+        let code = import::as_solution("impl Solution {\n    pub fn f() {}\n}\n");
+        assert!(code.starts_with("struct Solution;\n\nimpl Solution {"));
+        assert!(code.ends_with("\nfn main() {}\n"));
+        let code = "struct Solution;\nimpl Solution {}\nfn main() {}\n";
+        assert_eq!(import::as_solution(code), code);
+    }
+
+    #[test]
+    fn progress_is_exported() {
+        use chrono::{TimeZone, Utc};
+        use export::{render, ExportFormat, ExportRow};
+        use info::{Difficulty, Status};
+
+        let mut info = tracked(42, Difficulty::Hard, Status::Finished, "Array\nStack");
+        info.title = "Trapping \"Rain\", Water | II".to_owned();
+        info.link = "https://leetcode.com/problems/trapping-rain-water/".to_owned();
+        info.meta.created = Some(Utc.with_ymd_and_hms(2024, 3, 1, 10, 0, 0).unwrap());
+        info.meta.finished = Some(Utc.with_ymd_and_hms(2024, 3, 1, 11, 30, 0).unwrap());
+        let rows = [ExportRow::new(
+            &info,
+            vec!["main.rs".to_owned(), "stack.rs".to_owned()],
+        )];
+        assert_eq!(rows[0].hours_spent, Some(1.5));
+        assert_eq!(
+            "markdown".parse::<ExportFormat>().unwrap(),
+            ExportFormat::Md
+        );
+
+        let csv = render(&rows, ExportFormat::Csv).unwrap();
+        let line = csv.lines().nth(1).unwrap();
+        assert!(
+            line.starts_with("42,\"Trapping \"\"Rain\"\", Water | II\",https://"),
+            "{line}"
+        );
+        assert!(line.contains(",Hard,Array;Stack,Finished,2024-03-01T10:00:00+00:00,"));
+        assert!(line.ends_with(",1.5,main.rs;stack.rs"));
+
+        let md = render(&rows, ExportFormat::Md).unwrap();
+        let line = md.lines().nth(2).unwrap();
+        assert!(
+            line.contains("[Trapping \"Rain\", Water \\| II](https://"),
+            "{line}"
+        );
+        assert!(line.contains("| 2024-03-01 | 1.5 |"));
+        assert!(line.ends_with("[main.rs](src/42/src/main.rs), [stack.rs](src/42/src/stack.rs) |"));
+
+        let json: serde_json::Value =
+            serde_json::from_str(&render(&rows, ExportFormat::Json).unwrap()).unwrap();
+        assert_eq!(json[0]["tags"], serde_json::json!(["Array", "Stack"]));
+        assert_eq!(json[0]["directory"], "src/42/");
+    }
+
+    #[test]
+    fn readme_section_is_spliced_between_markers() {
+        use info::{Difficulty, Status};
+        use readme::{render_section, splice, END_MARKER, START_MARKER};
+
+        let problems = [
+            tracked(42, Difficulty::Hard, Status::Attempted, "Array"),
+            tracked(1, Difficulty::Easy, Status::Finished, "Array\nHashTable"),
+        ];
+        let section = render_section(&problems);
+        assert!(section.starts_with(START_MARKER) && section.ends_with(END_MARKER));
+        assert!(section.contains("**2** problems tracked: 1 finished, 1 attempted, 0 hidden."));
+        assert!(section.contains("https://img.shields.io/badge/Easy-1%2F1-brightgreen"));
+        assert!(section.contains("https://img.shields.io/badge/Hard-0%2F1-red"));
+        assert!(section.contains("| Array | 2 | 1 |\n| HashTable | 1 | 1 |"));
+        let one = section.find("| 1 | [Title 1](src/1/) | Easy | Finished | Array, HashTable |");
+        let forty_two = section.find("| 42 | [Title 42](src/42/) | Hard | Attempted | Array |");
+        assert!(one.unwrap() < forty_two.unwrap());
+
+        let readme = format!("# Notes\n\n{START_MARKER}\nold\n{END_MARKER}\nAfter\n");
+        assert_eq!(splice(&readme, "new").unwrap(), "# Notes\n\nnew\nAfter\n");
+        assert_eq!(splice("# Notes\n", "new").unwrap(), "# Notes\n\nnew\n");
+        assert_eq!(splice("", "new").unwrap(), "new\n");
+        assert!(splice(&format!("{END_MARKER}\n{START_MARKER}"), "new").is_err());
+        assert!(splice(START_MARKER, "new").is_err());
+    }
+
+    #[test]
+    fn commits_are_templated_and_read_back() {
+        use git::{message, parse_log, render, tag_details, Action};
+
+        let old = vec!["Array".to_owned(), "Stack".to_owned()];
+        let new = vec!["Array".to_owned(), "HashTable".to_owned()];
+        assert_eq!(tag_details(&old, &new), " (+HashTable, -Stack)");
+        assert_eq!(tag_details(&old, &old), "");
+        let line = render("new: {num}. {title}", 42, "Trapping Rain Water", "");
+        assert_eq!(line, "new: 42. Trapping Rain Water");
+        let line = render("finish: {num}{details}", 42, "", " (Accepted, 3 ms)");
+        assert_eq!(line, "finish: 42 (Accepted, 3 ms)");
+
+        assert_eq!(
+            message(Action::Finish, &[42], &[line]),
+            "finish: 42 (Accepted, 3 ms)\n\nLc-Action: finish\nLc-Problem: 42\n"
+        );
+        let lines = ["tag: 1 (+Array)".to_owned(), "tag: 2 (+Array)".to_owned()];
+        assert!(message(Action::Tag, &[1, 2], &lines)
+            .starts_with("tag: 2 problems\n\ntag: 1 (+Array)\ntag: 2 (+Array)\n\n"));
+
+        let output = "abc1234\x1f2024-03-01\x1ftag: 2 problems\x1ftag\x1f1, 2\n\x1e\n\
+                      def5678\x1f2024-02-29\x1fnew: 42. Trapping Rain Water\x1fnew\x1f42\n\x1e\n";
+        let entries = parse_log(output);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].action, Some(Action::Tag));
+        assert_eq!(entries[0].problems, vec![1, 2]);
+        assert_eq!(entries[1].date, "2024-02-29");
+        assert_eq!(entries[1].subject, "new: 42. Trapping Rain Water");
+
+        let repo = repo::Repo::in_memory();
+        assert_eq!(git::touched(&repo, Action::Finish, 42), vec![repo.meta(42)]);
+        assert_eq!(git::touched(&repo, Action::Tag, 42), vec![repo.tags(42)]);
+        assert_eq!(git::touched(&repo, Action::New, 42).len(), 4);
+    }
+
+    #[test]
+    fn finish_commits_carry_the_accepted_verdict() {
+        use chrono::{Duration, Utc};
+        use info::{LastSubmission, Meta, Status};
+
+        let root = std::env::temp_dir().join(format!("lc_git_{}", std::process::id()));
+        let repo = repo::Repo::at(&root);
+        let git = |args: &[&str]| {
+            let out = std::process::Command::new("git")
+                .arg("-C")
+                .arg(&root)
+                .args(args)
+                .output()
+                .unwrap();
+            String::from_utf8_lossy(&out.stdout).trim().to_owned()
+        };
+        let submitted = |status: &str, runtime| LastSubmission {
+            status: status.to_owned(),
+            runtime,
+            submitted: Utc::now() - Duration::minutes(5),
+        };
+        for num in [42, 7] {
+            std::fs::create_dir_all(repo.solution_dir(num)).unwrap();
+            std::fs::write(repo.problem_readme(num), format!("# {num}. Title {num}\n")).unwrap();
+        }
+        let meta = |last_submission| Meta {
+            status: Status::Attempted,
+            last_submission: Some(last_submission),
+            ..Default::default()
+        };
+        meta(submitted("Accepted", Some("3 ms".to_owned())))
+            .write(&repo, 42)
+            .unwrap();
+        meta(submitted("Wrong Answer", None))
+            .write(&repo, 7)
+            .unwrap();
+        git(&["init", "-q"]);
+        git(&["config", "user.email", "lc@example.com"]);
+        git(&["config", "user.name", "lc"]);
+        git(&["add", "-A"]);
+        git(&["commit", "-q", "-m", "start"]);
+
+        let finish = |num| {
+            let before = git::Snapshot::take(&repo).unwrap();
+            finish::run(&repo, &num).unwrap();
+            git::commit(&repo, git::Action::Finish, &before).unwrap();
+            git(&["log", "-1", "--format=%s"])
+        };
+        let accepted = finish(42);
+        let rejected = finish(7);
+        // finished again after hiding, with no submission since the first finish
+        hide::run(&repo, &42).unwrap();
+        git(&["commit", "-qam", "hide"]);
+        let again = finish(42);
+        std::fs::remove_dir_all(&root).unwrap();
+        assert_eq!(accepted, "finish: 42 (Accepted, 3 ms)");
+        assert_eq!(rejected, "finish: 7");
+        assert_eq!(again, "finish: 42");
+    }
+
+    #[test]
+    fn cached_responses_are_keyed_and_expire() {
+        use cache::{CacheEntry, CacheKey};
+        use chrono::{Duration, Utc};
+
+        let query = |variables: serde_json::Value| serde_json::json!({"query": "...", "variables": variables, "operationName": "questionEditorData"});
+        let key = CacheKey::for_session(&query(serde_json::json!({"titleSlug": "two-sum"})), None);
+        assert_eq!(key.operation, "questionEditorData");
+        assert_eq!(
+            key.file_name(),
+            CacheKey::of(&query(serde_json::json!({"titleSlug": "two-sum"}))).file_name()
+        );
+        assert_ne!(
+            key.file_name(),
+            CacheKey::of(&query(serde_json::json!({"titleSlug": "add-two-numbers"}))).file_name()
+        );
+        assert!(key.file_name().starts_with("questionEditorData-"));
+
+        let now = Utc::now();
+        let entry = |operation: &str, age: Duration| CacheEntry {
+            key: CacheKey {
+                operation: operation.to_owned(),
+                variables: serde_json::Value::Null,
+                account: None,
+            },
+            fetched: now - age,
+            response: serde_json::Value::Null,
+        };
+        assert!(entry("questionEditorData", Duration::days(29)).is_fresh(now));
+        assert!(!entry("questionEditorData", Duration::days(31)).is_fresh(now));
+        assert!(!entry("questionOfToday", Duration::hours(2)).is_fresh(now));
+        // queries without a TTL, or whose answer changes with every solve, are never answered
+        // from the cache while online
+        assert!(!entry("somethingElse", Duration::zero()).is_fresh(now));
+        assert!(!entry("problemsetQuestionList", Duration::seconds(1)).is_fresh(now));
+
+        // answers that depend on the account aren't shared between sessions
+        let list = serde_json::json!({"variables": {"skip": 0}, "operationName": "submissionList"});
+        let mine = CacheKey::for_session(&list, Some("session-a"));
+        assert_ne!(
+            mine.file_name(),
+            CacheKey::for_session(&list, Some("session-b")).file_name()
+        );
+        assert!(!mine.account.as_ref().unwrap().contains("session-a"));
+        let public = query(serde_json::json!({"titleSlug": "two-sum"}));
+        assert_eq!(CacheKey::for_session(&public, Some("session-a")), key);
+    }
+
+    #[test]
+    fn request_failures_are_typed() {
+        use chrono::{TimeZone, Utc};
+        use error::ErrorKind;
+        use request::{backoff, graphql_error, retry_after};
+        use std::time::Duration;
+
+        let now = Utc.with_ymd_and_hms(2024, 3, 1, 12, 0, 0).unwrap();
+        assert_eq!(retry_after("7", now), Some(Duration::from_secs(7)));
+        assert_eq!(
+            retry_after("Fri, 01 Mar 2024 12:00:30 GMT", now),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(
+            retry_after("Fri, 01 Mar 2024 11:00:00 GMT", now),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(retry_after("soon", now), None);
+
+        assert_eq!(backoff(0, 0), Duration::from_millis(500));
+        assert_eq!(backoff(2, 0), Duration::from_millis(2000));
+        assert!(backoff(2, 999) < Duration::from_millis(3000));
+
+        let errors =
+            |message: &str| serde_json::json!({"errors": [{"message": message}], "data": null});
+        let kind = |message: &str| graphql_error(&errors(message)).map(|a| a.kind());
+        assert_eq!(
+            kind("That question does not exist."),
+            Some(ErrorKind::NotFound)
+        );
+        assert_eq!(kind("Subscribe to unlock"), Some(ErrorKind::PremiumOnly));
+        assert_eq!(kind("User is not authenticated"), Some(ErrorKind::Auth));
+        assert_eq!(kind("Something broke"), Some(ErrorKind::Network));
+        assert!(graphql_error(&serde_json::json!({"data": {}})).is_none());
+
+        let e = new::parse_from_json_to_problem(serde_json::json!({"data": {"question": null}}))
+            .unwrap_err();
+        assert_eq!(ErrorKind::of(&e), ErrorKind::NotFound);
+        let premium = serde_json::json!({"data": {"question": {
+            "questionId": "1", "questionFrontendId": "1", "isPaidOnly": true, "codeSnippets": null
+        }}});
+        let e = new::parse_from_json_to_problem(premium).unwrap_err();
+        assert!(matches!(e.downcast_ref(), Some(error::Error::PremiumOnly)));
+    }
+
+    #[test]
+    fn errors_have_kinds_and_exit_codes() {
+        use error::{Error, ErrorKind};
+        use std::collections::HashSet;
+        use std::error::Error as _;
+        use strum::IntoEnumIterator;
+
+        let codes = ErrorKind::iter()
+            .map(|a| a.exit_code())
+            .collect::<HashSet<_>>();
+        assert_eq!(codes.len(), ErrorKind::iter().count());
+        assert!(!codes.contains(&0) && !codes.contains(&2));
+
+        let e: anyhow::Error = Error::NotTracked(42).into();
+        assert_eq!(e.to_string(), "Problem 42 is not tracked in the repo!");
+        assert_eq!(ErrorKind::of(&e), ErrorKind::NotTracked);
+        // the kind is still found under context added on top
+        let e = e.context("Could not finish");
+        assert_eq!(ErrorKind::of(&e), ErrorKind::NotTracked);
+
+        let e = Error::config("LEETCODE_DIR is not set").caused_by(std::env::VarError::NotPresent);
+        assert!(e.source().is_some());
+        assert_eq!(e.kind(), ErrorKind::Config);
+
+        let missing = std::path::Path::new("/nonexistent/lc/TAGS");
+        let e = fs::read_to_string(missing).unwrap_err();
+        assert!(matches!(&e, Error::Filesystem { path, .. } if path == missing));
+        assert!(e.source().is_some());
+        assert_eq!(fs::read_optional(missing).unwrap(), None);
+
+        // errors from the libraries lc uses are sorted by where they came from
+        let e = anyhow::Error::from("x".parse::<usize>().unwrap_err());
+        assert_eq!(ErrorKind::of(&e), ErrorKind::Parse);
+        let e = anyhow::Error::msg("No problems were given");
+        assert_eq!(ErrorKind::of(&e), ErrorKind::Other);
+        assert_eq!(
+            ErrorKind::of(&link::ProblemRef::parse("https://example.com/").unwrap_err()),
+            ErrorKind::Parse
+        );
+    }
+
+    #[test]
+    fn repos_are_found_by_their_marker() {
+        use repo::Repo;
+        use std::path::Path;
+
+        let repo = Repo::at("/home/me/leetcode");
+        assert_eq!(
+            repo.main_rs(42),
+            Path::new("/home/me/leetcode/src/42/src/main.rs")
+        );
+        assert_eq!(
+            repo.meta(42),
+            Path::new("/home/me/leetcode/src/42/META.json")
+        );
+        assert_eq!(repo.relative(&repo.tags(1)), Path::new("src/1/TAGS"));
+        // a trailing slash, as LEETCODE_DIR used to need, changes nothing
+        assert_eq!(Repo::at("/home/me/leetcode/").catalog(), repo.catalog());
+
+        let root = std::env::temp_dir().join(format!("lc_repo_{}", std::process::id()));
+        let nested = root.join("src").join("42").join("src");
+        std::fs::create_dir_all(&nested).unwrap();
+        assert_ne!(
+            Repo::find(&nested).map(|a| a.root().to_owned()),
+            Some(root.clone())
+        );
+        std::fs::write(root.join(repo::MARKER), "").unwrap();
+        let found = Repo::find(&nested).map(|a| a.root().to_owned());
+        std::fs::remove_dir_all(&root).unwrap();
+        assert_eq!(found, Some(root));
+    }
+
+    #[test]
+    fn commands_run_against_an_in_memory_repo() {
+        use error::{Error, ErrorKind};
+        use info::Status;
+        use tag::{TagCommand, TagType};
+
+        let repo = repo::Repo::in_memory();
+        let with_code = |num: usize, code: &str| {
+            new::parse_from_json_to_problem(serde_json::json!({"data": {"question": {
+                "questionId": num.to_string(), "questionFrontendId": num.to_string(),
+                "title": "Two Sum", "titleSlug": "two-sum", "difficulty": "Easy",
+                "codeSnippets": [{"langSlug": "rust", "code": code}]
+            }}}))
+            .unwrap()
+        };
+        let problem = |num: usize| {
+            with_code(
+                num,
+                "impl Solution {\n    pub fn two_sum(nums: Vec<i32>, target: i32) -> Vec<i32> {\n    }\n}",
+            )
+        };
+        // a failed scaffold leaves Cargo.toml alone instead of pointing it at a missing main.rs
+        let cargo = repo.read(&repo.cargo_toml()).unwrap();
+        assert!(new::create_entry(&repo, with_code(3, "struct MinStack;")).is_err());
+        assert_eq!(repo.read(&repo.cargo_toml()).unwrap(), cargo);
+        // solutions written by hand have comments and helpers before the function to call
+        let solution = "impl Solution {\n    // fn sketch() first\n    #[inline]\n    fn helper() {}\n    pub fn two_sum() {}\n}";
+        assert_eq!(new::solution_function(solution).as_deref(), Some("two_sum"));
+        new::create_entry(&repo, problem(1)).unwrap();
+        assert_eq!(common::tracked_problems(&repo).unwrap(), vec![1]);
+        assert!(repo
+            .read(&repo.main_rs(1))
+            .unwrap()
+            .contains("Solution::two_sum()"));
+        assert!(repo
+            .read(&repo.cargo_toml())
+            .unwrap()
+            .ends_with("name = \"1\"\npath = \"src/1/src/main.rs\"\n"));
+        let e = new::create_entry(&repo, problem(1)).unwrap_err();
+        assert!(matches!(e.downcast_ref(), Some(Error::AlreadyExists(1))));
+
+        let tags = |cmd| tag::tag_subcommands(&repo, &cmd).unwrap();
+        tags(TagCommand::Add {
+            problems: Some("1".parse().unwrap()),
+            tags: vec![TagType::Stack, TagType::Graph],
+        });
+        tags(TagCommand::Remove {
+            problems: Some("1".parse().unwrap()),
+            tags: vec![TagType::Stack],
+        });
+        assert_eq!(repo.read(&repo.tags(1)).unwrap(), "Graph\n");
+        assert_eq!(tag::search_tags(&repo, &[TagType::Graph]).unwrap(), vec![1]);
+
+        finish::run(&repo, &1).unwrap();
+        let info = info::load(&repo, 1).unwrap();
+        assert_eq!(
+            (info.title.as_str(), info.meta.status),
+            ("Two Sum", Status::Finished)
+        );
+        // hiding and finishing again keeps the first finish
+        info::set_status(&repo, 1, Status::Hidden).unwrap();
+        finish::run(&repo, &1).unwrap();
+        assert_eq!(
+            info::load(&repo, 1).unwrap().meta.finished,
+            info.meta.finished
+        );
+        let e = finish::run(&repo, &2).unwrap_err();
+        assert_eq!(ErrorKind::of(&e), ErrorKind::NotTracked);
+
+        // clones share the files, and missing files fail as they would on disk
+        let clone = repo.clone();
+        clone.remove_file(&clone.tags(1)).unwrap();
+        assert!(!repo.exists(&repo.tags(1)));
+        assert_eq!(repo.read_optional(&repo.tags(1)).unwrap(), None);
+        let e = repo.remove_file(&repo.tags(1)).unwrap_err();
+        assert!(
+            matches!(&e, Error::Filesystem { source, .. } if source.kind() == std::io::ErrorKind::NotFound)
+        );
+        assert!(repo.write(&repo.main_rs(2), "").is_err());
+        assert!(repo.append(&repo.custom_tags(), "Blind75\n").is_err());
+    }
+
+    #[tokio::test]
+    async fn requests_are_retried_when_rate_limited() {
+        use std::io::{BufRead, BufReader, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let responses = [
+            "HTTP/1.1 429 Too Many Requests\r\nRetry-After: 0\r\nContent-Length: 0\r\n\r\n",
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: 11\r\n\r\n{\"ok\":true}",
+            "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nContent-Length: 6\r\n\r\n<html>",
+            "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n",
+        ];
+        std::thread::spawn(move || {
+            for resp in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut line = String::new();
+                while reader.read_line(&mut line).unwrap() > 2 {
+                    line.clear();
+                }
+                stream.write_all(resp.as_bytes()).unwrap();
+            }
+        });
+
+        let client = reqwest::Client::new();
+        let url = format!("http://{addr}/");
+        let get = || client.get(&url).header("Connection", "close");
+        let json = request::send_json(get(), true).await.unwrap();
+        assert_eq!(json, serde_json::json!({"ok": true}));
+        let e = request::send_json(get(), true).await.unwrap_err();
+        assert_eq!(error::ErrorKind::of(&e), error::ErrorKind::Network);
+        let e = request::send_json(get(), true).await.unwrap_err();
+        assert_eq!(error::ErrorKind::of(&e), error::ErrorKind::NotFound);
+    }
 }
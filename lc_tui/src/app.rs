@@ -0,0 +1,186 @@
+// state of the TUI, independent of how it is drawn
+use anyhow::Result;
+use lc_lib::info::{load_all, ProblemInfo};
+use lc_lib::repo::Repo;
+use ratatui::text::Line;
+use ratatui::widgets::{ListState, TableState};
+
+use crate::{
+    live::Watcher,
+    markdown,
+    runs::{RunKind, Runs},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Focus {
+    Tags,
+    Problems,
+    Runs,
+}
+
+pub struct App {
+    pub repo: Repo,
+    pub problems: Vec<ProblemInfo>,
+    /// Every tag used by at least one problem, the sidebar shows these after "All"
+    pub tags: Vec<String>,
+    pub tag_state: ListState,
+    pub table_state: TableState,
+    pub focus: Focus,
+    /// How far the detail pane is scrolled down
+    pub detail_scroll: u16,
+    /// The rendered README of a problem, kept until another one is shown or the repo is reread
+    statement: Option<(usize, Vec<Line<'static>>)>,
+    /// Shown at the bottom of the screen, the outcome of the last action
+    pub message: String,
+    pub runs: Runs,
+    /// How many lines the runs pane is scrolled up from the latest output
+    pub runs_scroll: u16,
+    pub watcher: Watcher,
+    pub quit: bool,
+}
+
+impl App {
+    pub fn new() -> Result<App> {
+        let repo = Repo::open()?;
+        let mut app = App {
+            watcher: Watcher::start(repo.clone()),
+            repo,
+            problems: Vec::new(),
+            tags: Vec::new(),
+            tag_state: ListState::default().with_selected(Some(0)),
+            table_state: TableState::default().with_selected(Some(0)),
+            focus: Focus::Problems,
+            detail_scroll: 0,
+            statement: None,
+            message: String::new(),
+            runs: Runs::new(),
+            runs_scroll: 0,
+            quit: false,
+        };
+        app.reload()?;
+        Ok(app)
+    }
+
+    /// Rereads every problem from the repo, keeping the same problem selected. This reads every
+    /// `META.json` and `TAGS`, so it is only done after something changed them.
+    pub fn reload(&mut self) -> Result<()> {
+        let selected = self.selected().map(|a| a.number);
+        let filter = self.tag_filter().cloned();
+        self.statement = None;
+
+        self.problems = load_all(&self.repo)?;
+        self.tags = self
+            .problems
+            .iter()
+            .flat_map(|a| a.tags.iter().cloned())
+            .collect();
+        self.tags.sort();
+        self.tags.dedup();
+
+        let tag_index = filter
+            .and_then(|tag| self.tags.iter().position(|a| *a == tag))
+            .map_or(0, |i| i + 1);
+        self.tag_state.select(Some(tag_index));
+        let index = selected
+            .and_then(|num| self.visible().iter().position(|a| a.number == num))
+            .unwrap_or(0);
+        self.table_state.select(Some(index));
+        self.watcher.select(self.selected().map(|a| a.number));
+        Ok(())
+    }
+
+    /// Reruns the tests of saved problems and picks up the runs that finished in the background,
+    /// rereading the repo when one did since a submission is recorded in `META.json`.
+    pub fn update_runs(&mut self) -> Result<()> {
+        for num in self.watcher.drain() {
+            self.start_run(num, RunKind::Local);
+        }
+        let finished = self.runs.update();
+        for run in &finished {
+            self.message = format!("Problem {} {}: {}", run.num, run.kind, run.state.compact());
+        }
+        match finished.is_empty() {
+            true => Ok(()),
+            false => self.reload(),
+        }
+    }
+
+    /// The statement of problem `num`, read and rendered the first time it is shown.
+    pub fn statement(&mut self, num: usize) -> &[Line<'static>] {
+        if self.statement.as_ref().is_none_or(|a| a.0 != num) {
+            let readme = self
+                .repo
+                .read_optional(&self.repo.problem_readme(num))
+                .ok()
+                .flatten()
+                .unwrap_or_default();
+            self.statement = Some((num, markdown::render(&readme)));
+        }
+        self.statement.as_ref().map_or(&[], |a| &a.1)
+    }
+
+    pub fn start_run(&mut self, num: usize, kind: RunKind) {
+        self.runs.start(&self.repo, num, kind);
+        self.runs_scroll = 0;
+        self.message = format!("Started {kind} for Problem {num}");
+    }
+
+    /// The tag selected in the sidebar, `None` when showing all problems.
+    pub fn tag_filter(&self) -> Option<&String> {
+        match self.tag_state.selected() {
+            Some(0) | None => None,
+            Some(i) => self.tags.get(i - 1),
+        }
+    }
+
+    /// The problems that match the tag filter.
+    pub fn visible(&self) -> Vec<&ProblemInfo> {
+        let filter = self.tag_filter();
+        self.problems
+            .iter()
+            .filter(|a| filter.is_none_or(|tag| a.tags.contains(tag)))
+            .collect()
+    }
+
+    pub fn selected(&self) -> Option<&ProblemInfo> {
+        let index = self.table_state.selected()?;
+        self.visible().get(index).copied()
+    }
+
+    /// Moves the selection of the focused pane by `delta` rows, wrapping around at the ends.
+    pub fn move_selection(&mut self, delta: isize) {
+        let (len, state_index) = match self.focus {
+            Focus::Runs => {
+                // the runs pane scrolls instead, down is towards the latest output
+                self.scroll_runs(-delta);
+                return;
+            }
+            Focus::Tags => (self.tags.len() + 1, self.tag_state.selected()),
+            Focus::Problems => (self.visible().len(), self.table_state.selected()),
+        };
+        if len == 0 {
+            return;
+        }
+        let index = (state_index.unwrap_or(0) as isize + delta).rem_euclid(len as isize) as usize;
+        if self.focus == Focus::Tags {
+            self.tag_state.select(Some(index));
+            self.table_state.select(Some(0));
+        } else {
+            self.table_state.select(Some(index));
+        }
+        self.detail_scroll = 0;
+        self.watcher.select(self.selected().map(|a| a.number));
+    }
+
+    pub fn scroll_runs(&mut self, delta: isize) {
+        self.runs_scroll = (self.runs_scroll as isize + delta).max(0) as u16;
+    }
+
+    pub fn toggle_focus(&mut self) {
+        self.focus = match self.focus {
+            Focus::Tags => Focus::Problems,
+            Focus::Problems => Focus::Runs,
+            Focus::Runs => Focus::Tags,
+        };
+    }
+}
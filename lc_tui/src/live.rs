@@ -0,0 +1,60 @@
+// noticing when the files of the selected problem are saved
+use lc_lib::local::latest_change;
+use lc_lib::repo::Repo;
+use std::{
+    sync::{
+        mpsc::{channel, Receiver, Sender},
+        Arc, Mutex,
+    },
+    time::{Duration, SystemTime},
+};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Watches the solution of the selected problem on a background thread so its tests can be rerun
+/// every time one of its files is saved.
+pub struct Watcher {
+    selected: Arc<Mutex<Option<usize>>>,
+    changes: Receiver<usize>,
+}
+
+impl Watcher {
+    pub fn start(repo: Repo) -> Watcher {
+        let selected = Arc::new(Mutex::new(None));
+        let (tx, changes) = channel();
+        let watched = selected.clone();
+        std::thread::spawn(move || watch(&repo, watched, tx));
+        Watcher { selected, changes }
+    }
+
+    /// Changes which problem is watched.
+    pub fn select(&self, num: Option<usize>) {
+        if let Ok(mut selected) = self.selected.lock() {
+            *selected = num;
+        }
+    }
+
+    /// The problems that changed since the last call.
+    pub fn drain(&self) -> Vec<usize> {
+        let mut out = self.changes.try_iter().collect::<Vec<_>>();
+        out.dedup();
+        out
+    }
+}
+
+fn watch(repo: &Repo, selected: Arc<Mutex<Option<usize>>>, changes: Sender<usize>) {
+    let mut watching: Option<(usize, SystemTime)> = None;
+    loop {
+        std::thread::sleep(POLL_INTERVAL);
+        let Some(num) = *selected.lock().unwrap() else {
+            continue;
+        };
+        let change = latest_change(repo, &repo.solution_dir(num)).unwrap_or(SystemTime::UNIX_EPOCH);
+        // switching problems only records where it is at, tests run on the next save
+        let changed = watching.is_some_and(|(watched, last)| watched == num && change > last);
+        watching = Some((num, change));
+        if changed && changes.send(num).is_err() {
+            return;
+        }
+    }
+}
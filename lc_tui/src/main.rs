@@ -1,5 +1,156 @@
-use lc_lib;
+mod app;
+mod live;
+mod markdown;
+mod runs;
+mod ui;
 
-fn main() {
-    println!("Hello, world!");
+#[cfg(test)]
+mod test;
+
+use anyhow::Result;
+use crossterm::{
+    event::{self, Event, KeyCode, KeyEventKind},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use lc_lib::info::{set_status, Status};
+use ratatui::{backend::CrosstermBackend, Terminal};
+use std::{io::stdout, time::Duration};
+
+use app::{App, Focus};
+use runs::RunKind;
+
+type Term = Terminal<CrosstermBackend<std::io::Stdout>>;
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let mut app = App::new()?;
+
+    enable_raw_mode()?;
+    execute!(stdout(), EnterAlternateScreen)?;
+    // put the terminal back before a panic message is printed
+    let hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let _ = disable_raw_mode();
+        let _ = execute!(stdout(), LeaveAlternateScreen);
+        hook(info);
+    }));
+    let mut terminal = Terminal::new(CrosstermBackend::new(stdout()))?;
+
+    let res = run(&mut terminal, &mut app).await;
+
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    terminal.show_cursor()?;
+    res
+}
+
+async fn run(terminal: &mut Term, app: &mut App) -> Result<()> {
+    while !app.quit {
+        if let Err(e) = app.update_runs() {
+            app.message = format!("Error: {e}");
+        }
+        terminal.draw(|f| ui::draw(f, app))?;
+        if !event::poll(Duration::from_millis(250))? {
+            continue;
+        }
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        let num = app.selected().map(|a| a.number);
+        // the rest only move around or start runs, which reload once they finish
+        let changes_files =
+            num.is_some() && matches!(key.code, KeyCode::Char('e' | 't' | 'f' | 'h' | 'a'));
+        let res = match (key.code, num) {
+            (KeyCode::Char('q') | KeyCode::Esc, _) => {
+                app.quit = true;
+                Ok(())
+            }
+            (KeyCode::Tab, _) => {
+                app.toggle_focus();
+                Ok(())
+            }
+            (KeyCode::Down | KeyCode::Char('j'), _) => {
+                app.move_selection(1);
+                Ok(())
+            }
+            (KeyCode::Up | KeyCode::Char('k'), _) => {
+                app.move_selection(-1);
+                Ok(())
+            }
+            (KeyCode::PageDown, _) if app.focus == Focus::Runs => {
+                app.scroll_runs(-10);
+                Ok(())
+            }
+            (KeyCode::PageUp, _) if app.focus == Focus::Runs => {
+                app.scroll_runs(10);
+                Ok(())
+            }
+            (KeyCode::PageDown, _) => {
+                app.detail_scroll = app.detail_scroll.saturating_add(10);
+                Ok(())
+            }
+            (KeyCode::PageUp, _) => {
+                app.detail_scroll = app.detail_scroll.saturating_sub(10);
+                Ok(())
+            }
+            (KeyCode::Enter, _) if app.focus == Focus::Tags => {
+                app.focus = Focus::Problems;
+                Ok(())
+            }
+            (KeyCode::Char('e'), Some(num)) => suspend(terminal, || {
+                lc_lib::edit::run(&app.repo, &num, false, false)
+            })
+            .map(|_| {
+                app.message = format!("Edited Problem {num}");
+            }),
+            (KeyCode::Char('t'), Some(num)) => {
+                suspend(terminal, || lc_lib::tag::edit_tags(&app.repo, num))
+                    .map(|_| app.message = format!("Updated the tags of Problem {num}"))
+            }
+            (KeyCode::Char('r'), Some(num)) => {
+                app.start_run(num, RunKind::Local);
+                Ok(())
+            }
+            (KeyCode::Char('x'), Some(num)) => {
+                app.start_run(num, RunKind::Examples);
+                Ok(())
+            }
+            (KeyCode::Char('s'), Some(num)) => {
+                app.start_run(num, RunKind::Submit);
+                Ok(())
+            }
+            (KeyCode::Char('f'), Some(num)) => change_status(app, num, Status::Finished),
+            (KeyCode::Char('h'), Some(num)) => change_status(app, num, Status::Hidden),
+            (KeyCode::Char('a'), Some(num)) => change_status(app, num, Status::Attempted),
+            _ => Ok(()),
+        };
+        if let Err(e) = res.and_then(|_| match changes_files {
+            true => app.reload(),
+            false => Ok(()),
+        }) {
+            app.message = format!("Error: {e}");
+        }
+    }
+    Ok(())
+}
+
+fn change_status(app: &mut App, num: usize, status: Status) -> Result<()> {
+    set_status(&app.repo, num, status)?;
+    app.message = format!("Problem {num} is now {status}");
+    Ok(())
+}
+
+/// Hands the terminal over to `f`, e.g. to run an editor, and takes it back afterwards.
+fn suspend<T>(terminal: &mut Term, f: impl FnOnce() -> Result<T>) -> Result<T> {
+    disable_raw_mode()?;
+    execute!(stdout(), LeaveAlternateScreen)?;
+    let out = f();
+    enable_raw_mode()?;
+    execute!(stdout(), EnterAlternateScreen)?;
+    terminal.clear()?;
+    out
 }
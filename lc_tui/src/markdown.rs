@@ -0,0 +1,265 @@
+// rendering the Markdown of a problem's README into styled lines
+use pulldown_cmark::{Event, HeadingLevel, Parser, Tag};
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+};
+
+/// Renders Markdown into lines for a `Paragraph`.
+///
+/// Handles what problem statements use: headings, paragraphs, bold and italic text, inline code,
+/// code blocks, nested lists, links, and `<sup>`/`<sub>` which are shown with unicode characters.
+pub fn render(markdown: &str) -> Vec<Line<'static>> {
+    let mut out = Renderer::default();
+    for event in Parser::new(markdown) {
+        out.event(event);
+    }
+    out.flush();
+    while out.lines.last().is_some_and(|a| a.spans.is_empty()) {
+        out.lines.pop();
+    }
+    out.lines
+}
+
+#[derive(Default)]
+struct Renderer {
+    lines: Vec<Line<'static>>,
+    current: Vec<Span<'static>>,
+    styles: Vec<Style>,
+    // None for an unordered list, otherwise the number of the next item
+    lists: Vec<Option<u64>>,
+    in_code_block: bool,
+    sup: bool,
+    sub: bool,
+}
+
+impl Renderer {
+    fn style(&self) -> Style {
+        self.styles
+            .iter()
+            .fold(Style::default(), |acc, a| acc.patch(*a))
+    }
+
+    fn push(&mut self, text: String, style: Style) {
+        self.current.push(Span::styled(text, style));
+    }
+
+    fn flush(&mut self) {
+        if !self.current.is_empty() {
+            self.lines
+                .push(Line::from(std::mem::take(&mut self.current)));
+        }
+    }
+
+    fn blank(&mut self) {
+        self.flush();
+        if self.lines.last().is_some_and(|a| !a.spans.is_empty()) {
+            self.lines.push(Line::default());
+        }
+    }
+
+    fn event(&mut self, event: Event) {
+        match event {
+            Event::Start(tag) => self.start(tag),
+            Event::End(tag) => self.end(tag),
+            Event::Text(text) if self.in_code_block => {
+                let style = Style::default().fg(Color::LightYellow);
+                for line in text.lines() {
+                    self.push(format!("  {line}"), style);
+                    self.flush();
+                }
+            }
+            Event::Text(text) => {
+                let text = if self.sup {
+                    text.chars().map(superscript).collect()
+                } else if self.sub {
+                    text.chars().map(subscript).collect()
+                } else {
+                    text.into_string()
+                };
+                let style = self.style();
+                self.push(text, style);
+            }
+            Event::Code(code) => {
+                // statements put superscripts inside of code, where Markdown keeps them as text
+                let style = self.style().fg(Color::Yellow);
+                self.push(inline_scripts(&code), style);
+            }
+            Event::Html(html) => match html.trim().to_ascii_lowercase().as_str() {
+                "<sup>" => self.sup = true,
+                "</sup>" => self.sup = false,
+                "<sub>" => self.sub = true,
+                "</sub>" => self.sub = false,
+                _ => {}
+            },
+            Event::SoftBreak => {
+                let style = self.style();
+                self.push(" ".to_owned(), style);
+            }
+            Event::HardBreak => self.flush(),
+            Event::Rule => {
+                self.flush();
+                self.lines
+                    .push(Line::from("─".repeat(40)).style(Style::default().fg(Color::DarkGray)));
+                self.blank();
+            }
+            _ => {}
+        }
+    }
+
+    fn start(&mut self, tag: Tag) {
+        match tag {
+            Tag::Heading(level, _, _) => {
+                self.blank();
+                let color = match level {
+                    HeadingLevel::H1 => Color::Cyan,
+                    _ => Color::LightCyan,
+                };
+                self.styles
+                    .push(Style::default().fg(color).add_modifier(Modifier::BOLD));
+            }
+            Tag::CodeBlock(_) => {
+                self.blank();
+                self.in_code_block = true;
+            }
+            Tag::List(start) => {
+                self.flush();
+                self.lists.push(start);
+            }
+            Tag::Item => {
+                self.flush();
+                let indent = "  ".repeat(self.lists.len().saturating_sub(1));
+                let marker = match self.lists.last_mut() {
+                    Some(Some(n)) => {
+                        *n += 1;
+                        format!("{indent}{}. ", *n - 1)
+                    }
+                    _ => format!("{indent}• "),
+                };
+                self.push(marker, Style::default().fg(Color::DarkGray));
+            }
+            Tag::Emphasis => self
+                .styles
+                .push(Style::default().add_modifier(Modifier::ITALIC)),
+            Tag::Strong => self
+                .styles
+                .push(Style::default().add_modifier(Modifier::BOLD)),
+            Tag::Link(..) => self.styles.push(
+                Style::default()
+                    .fg(Color::Blue)
+                    .add_modifier(Modifier::UNDERLINED),
+            ),
+            Tag::Image(..) => self.push("[image]".to_owned(), Style::default().fg(Color::DarkGray)),
+            _ => {}
+        }
+    }
+
+    fn end(&mut self, tag: Tag) {
+        match tag {
+            Tag::Paragraph => {
+                // paragraphs inside of list items are kept tight
+                if self.lists.is_empty() {
+                    self.blank();
+                } else {
+                    self.flush();
+                }
+            }
+            Tag::Heading(..) => {
+                self.styles.pop();
+                self.blank();
+            }
+            Tag::CodeBlock(_) => {
+                self.in_code_block = false;
+                self.blank();
+            }
+            Tag::List(_) => {
+                self.lists.pop();
+                if self.lists.is_empty() {
+                    self.blank();
+                }
+            }
+            Tag::Item => self.flush(),
+            Tag::Emphasis | Tag::Strong | Tag::Link(..) => {
+                self.styles.pop();
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Replaces `<sup>..</sup>` and `<sub>..</sub>` written out in text with unicode characters.
+fn inline_scripts(text: &str) -> String {
+    let mut out = String::new();
+    let mut rest = text;
+    loop {
+        let sup = rest
+            .find("<sup>")
+            .map(|i| (i, "<sup>", "</sup>", superscript as fn(char) -> char));
+        let sub = rest
+            .find("<sub>")
+            .map(|i| (i, "<sub>", "</sub>", subscript as fn(char) -> char));
+        let next = match (sup, sub) {
+            (Some(a), Some(b)) => Some(if a.0 < b.0 { a } else { b }),
+            (a, b) => a.or(b),
+        };
+        let Some((start, open, close, map)) = next else {
+            break;
+        };
+        let Some(len) = rest[start + open.len()..].find(close) else {
+            break;
+        };
+        out.push_str(&rest[..start]);
+        out.extend(
+            rest[start + open.len()..start + open.len() + len]
+                .chars()
+                .map(map),
+        );
+        rest = &rest[start + open.len() + len + close.len()..];
+    }
+    out.push_str(rest);
+    out
+}
+
+fn superscript(c: char) -> char {
+    match c {
+        '0' => '⁰',
+        '1' => '¹',
+        '2' => '²',
+        '3' => '³',
+        '4' => '⁴',
+        '5' => '⁵',
+        '6' => '⁶',
+        '7' => '⁷',
+        '8' => '⁸',
+        '9' => '⁹',
+        '+' => '⁺',
+        '-' => '⁻',
+        '=' => '⁼',
+        '(' => '⁽',
+        ')' => '⁾',
+        'n' => 'ⁿ',
+        'i' => 'ⁱ',
+        a => a,
+    }
+}
+
+fn subscript(c: char) -> char {
+    match c {
+        '0' => '₀',
+        '1' => '₁',
+        '2' => '₂',
+        '3' => '₃',
+        '4' => '₄',
+        '5' => '₅',
+        '6' => '₆',
+        '7' => '₇',
+        '8' => '₈',
+        '9' => '₉',
+        '+' => '₊',
+        '-' => '₋',
+        '=' => '₌',
+        '(' => '₍',
+        ')' => '₎',
+        a => a,
+    }
+}
@@ -0,0 +1,216 @@
+// local test runs and LeetCode judgements running in the background, kept for the session
+use chrono::{DateTime, Local};
+use lc_lib::{
+    common::Stage,
+    interpret::interpret_with_progress,
+    local::{run_tests_with_progress, TestSummary},
+    repo::Repo,
+    submit::{submit_with_progress, Judgement},
+};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum::Display)]
+pub enum RunKind {
+    /// `cargo test` in the repo
+    #[strum(serialize = "tests")]
+    Local,
+    /// The example cases on LeetCode
+    #[strum(serialize = "examples")]
+    Examples,
+    #[strum(serialize = "submit")]
+    Submit,
+}
+
+#[derive(Debug, Clone)]
+pub enum RunState {
+    Stage(Stage),
+    Tests(TestSummary),
+    Judged(Judgement),
+    Failed(String),
+}
+
+impl RunState {
+    pub fn finished(&self) -> bool {
+        !matches!(self, RunState::Stage(_))
+    }
+
+    /// A one line description of the state, for the footer and the run headers.
+    pub fn compact(&self) -> String {
+        match self {
+            RunState::Stage(stage) => format!("{stage}..."),
+            RunState::Tests(summary) => summary.compact(),
+            RunState::Judged(judgement) => judgement
+                .to_string()
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .to_owned(),
+            RunState::Failed(e) => format!("Error: {e}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Run {
+    pub num: usize,
+    pub kind: RunKind,
+    pub started: DateTime<Local>,
+    pub state: RunState,
+}
+
+/// Every run started this session, oldest first. Runs report back over a channel so the UI
+/// never waits on them.
+pub struct Runs {
+    pub history: Vec<Run>,
+    tx: UnboundedSender<(usize, RunState)>,
+    rx: UnboundedReceiver<(usize, RunState)>,
+}
+
+impl Runs {
+    pub fn new() -> Runs {
+        let (tx, rx) = unbounded_channel();
+        Runs {
+            history: Vec::new(),
+            tx,
+            rx,
+        }
+    }
+
+    /// Starts a run of `kind` for problem `num` in the background.
+    pub fn start(&mut self, repo: &Repo, num: usize, kind: RunKind) {
+        let id = self.history.len();
+        self.history.push(Run {
+            num,
+            kind,
+            started: Local::now(),
+            state: RunState::Stage(Stage::Queued),
+        });
+        let tx = self.tx.clone();
+        let repo = repo.clone();
+        let progress = {
+            let tx = tx.clone();
+            move |stage| {
+                let _ = tx.send((id, RunState::Stage(stage)));
+            }
+        };
+        match kind {
+            RunKind::Local => {
+                tokio::task::spawn_blocking(move || {
+                    let state = match run_tests_with_progress(&repo, num, progress) {
+                        Ok(summary) => RunState::Tests(summary),
+                        Err(e) => RunState::Failed(e.to_string()),
+                    };
+                    let _ = tx.send((id, state));
+                });
+            }
+            RunKind::Examples | RunKind::Submit => {
+                tokio::spawn(async move {
+                    let res = match kind {
+                        RunKind::Examples => interpret_with_progress(&repo, num, progress).await,
+                        _ => submit_with_progress(&repo, num, progress).await,
+                    };
+                    let state = match res {
+                        Ok(judgement) => RunState::Judged(judgement),
+                        Err(e) => RunState::Failed(e.to_string()),
+                    };
+                    let _ = tx.send((id, state));
+                });
+            }
+        }
+    }
+
+    /// Applies the progress reported since the last call, returning the runs that finished.
+    pub fn update(&mut self) -> Vec<Run> {
+        let mut finished = Vec::new();
+        while let Ok((id, state)) = self.rx.try_recv() {
+            let Some(run) = self.history.get_mut(id) else {
+                continue;
+            };
+            // a late progress report can't undo a verdict
+            if run.state.finished() {
+                continue;
+            }
+            run.state = state;
+            if run.state.finished() {
+                finished.push(run.clone());
+            }
+        }
+        finished
+    }
+}
+
+/// An answer that didn't match what was expected, from a LeetCode case or a failed `assert_eq!`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch {
+    /// The input of the case or the name of the test
+    pub label: String,
+    pub actual: String,
+    pub expected: String,
+}
+
+/// The cases of a judgement whose output differs from the expected answer.
+pub fn judgement_mismatches(judgement: &Judgement) -> Vec<Mismatch> {
+    judgement
+        .output
+        .iter()
+        .zip(&judgement.expected)
+        .enumerate()
+        .filter(|(_, (actual, expected))| actual != expected)
+        .map(|(i, (actual, expected))| Mismatch {
+            label: judgement
+                .input
+                .get(i)
+                .map(|a| a.replace('\n', ", "))
+                .unwrap_or_else(|| format!("case {}", i + 1)),
+            actual: actual.clone(),
+            expected: expected.clone(),
+        })
+        .collect()
+}
+
+/// The `left`/`right` values of the `assert_eq!`s that failed in `cargo test` output,
+/// treating `left` as the actual value like `assert_eq!(solution, expected)` does.
+pub fn assertion_mismatches(output: &str) -> Vec<Mismatch> {
+    let mut out = Vec::new();
+    let mut test = String::new();
+    let mut left: Option<String> = None;
+    for line in output.lines() {
+        if let Some(name) = line
+            .strip_prefix("---- ")
+            .and_then(|a| a.strip_suffix(" stdout ----"))
+        {
+            test = name.to_owned();
+            left = None;
+        } else if let Some(value) = line.trim_start().strip_prefix("left:") {
+            left = Some(assertion_value(value));
+        } else if let Some(value) = line.trim_start().strip_prefix("right:") {
+            if let Some(actual) = left.take() {
+                out.push(Mismatch {
+                    label: test.clone(),
+                    actual,
+                    expected: assertion_value(value),
+                });
+            }
+        }
+    }
+    out
+}
+
+/// Older versions of Rust print the values as `` `value`, `` rather than `value`, with the
+/// location of the panic after the last one.
+fn assertion_value(value: &str) -> String {
+    let value = value.trim();
+    match value.strip_prefix('`').and_then(|a| a.split_once('`')) {
+        Some((value, _)) => value.to_owned(),
+        None => value.to_owned(),
+    }
+}
+
+/// The byte index where `a` and `b` first differ.
+pub fn first_difference(a: &str, b: &str) -> usize {
+    a.char_indices()
+        .zip(b.chars())
+        .find(|((_, x), y)| x != y)
+        .map(|((i, _), _)| i)
+        .unwrap_or(a.len().min(b.len()))
+}
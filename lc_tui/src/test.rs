@@ -0,0 +1,89 @@
+use crate::{
+    markdown::render,
+    runs::{assertion_mismatches, first_difference, judgement_mismatches, Mismatch},
+};
+use lc_lib::submit::Judgement;
+
+fn text(lines: &[ratatui::text::Line]) -> Vec<String> {
+    lines
+        .iter()
+        .map(|a| a.spans.iter().map(|a| a.content.as_ref()).collect())
+        .collect()
+}
+
+#[test]
+fn statement_markdown_is_rendered() {
+    let lines = render(
+        "# 1. Two Sum\n\nGiven `nums` and **target**.\n\n```\nInput: nums = [2,7]\n```\n\n- `1 <= n <= 10<sup>4</sup>`\n- 2<sup>31</sup> - 1\n",
+    );
+    assert_eq!(
+        text(&lines),
+        vec![
+            "1. Two Sum",
+            "",
+            "Given nums and target.",
+            "",
+            "  Input: nums = [2,7]",
+            "",
+            "• 1 <= n <= 10⁴",
+            "• 2³¹ - 1",
+        ]
+    );
+}
+
+#[test]
+fn failing_assertions_become_mismatches() {
+    let output = "running 2 tests
+test tests::first ... FAILED
+test tests::second ... FAILED
+
+failures:
+
+---- tests::first stdout ----
+thread 'tests::first' panicked at src/1/src/main.rs:20:9:
+assertion `left == right` failed
+  left: [0, 1]
+ right: [1, 0]
+
+---- tests::second stdout ----
+thread 'tests::second' panicked at 'assertion failed: `(left == right)`
+  left: `3`,
+ right: `4`', src/1/src/main.rs:25:9
+";
+    assert_eq!(
+        assertion_mismatches(output),
+        vec![
+            Mismatch {
+                label: "tests::first".to_owned(),
+                actual: "[0, 1]".to_owned(),
+                expected: "[1, 0]".to_owned(),
+            },
+            Mismatch {
+                label: "tests::second".to_owned(),
+                actual: "3".to_owned(),
+                expected: "4".to_owned(),
+            },
+        ]
+    );
+    assert_eq!(first_difference("[0, 1]", "[0, 2]"), 4);
+    assert_eq!(first_difference("[0]", "[0, 1]"), 2);
+}
+
+#[test]
+fn only_wrong_cases_are_mismatches() {
+    let judgement = Judgement {
+        status: "Accepted".to_owned(),
+        input: vec!["[2,7]\n9".to_owned(), "[3,3]\n6".to_owned()],
+        output: vec!["[0,1]".to_owned(), "[1,0]".to_owned()],
+        expected: vec!["[0,1]".to_owned(), "[0,1]".to_owned()],
+        ..Default::default()
+    };
+    assert_eq!(
+        judgement_mismatches(&judgement),
+        vec![Mismatch {
+            label: "[3,3], 6".to_owned(),
+            actual: "[1,0]".to_owned(),
+            expected: "[0,1]".to_owned(),
+        }]
+    );
+}
@@ -0,0 +1,269 @@
+// drawing the TUI
+use lc_lib::info::{Difficulty, ProblemInfo, Status};
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, Paragraph, Row, Table, Wrap},
+    Frame,
+};
+
+use crate::{
+    app::{App, Focus},
+    runs::{assertion_mismatches, first_difference, judgement_mismatches, Mismatch, Run, RunState},
+};
+
+const HELP: &str = "q quit | tab switch pane | e edit | r run tests | x run examples | s submit | t tags | f finish | h hide | a attempted";
+
+pub fn draw(f: &mut Frame, app: &mut App) {
+    let [main, footer] =
+        Layout::vertical([Constraint::Min(3), Constraint::Length(2)]).areas(f.size());
+    let [sidebar, right] =
+        Layout::horizontal([Constraint::Length(24), Constraint::Min(20)]).areas(main);
+    let [list, detail] =
+        Layout::vertical([Constraint::Percentage(45), Constraint::Percentage(55)]).areas(right);
+
+    draw_tags(f, app, sidebar);
+    draw_problems(f, app, list);
+    draw_detail(f, app, detail);
+
+    let footer_text = vec![
+        Line::from(app.message.as_str()),
+        Line::from(HELP).style(Style::default().fg(Color::DarkGray)),
+    ];
+    f.render_widget(Paragraph::new(footer_text), footer);
+}
+
+fn pane(title: &str, focused: bool) -> Block<'_> {
+    let style = if focused {
+        Style::default().fg(Color::Cyan)
+    } else {
+        Style::default()
+    };
+    Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(style)
+}
+
+fn highlight() -> Style {
+    Style::default().add_modifier(Modifier::REVERSED)
+}
+
+fn draw_tags(f: &mut Frame, app: &mut App, area: Rect) {
+    let items = std::iter::once("All".to_owned())
+        .chain(app.tags.iter().cloned())
+        .map(ListItem::new)
+        .collect::<Vec<_>>();
+    let list = List::new(items)
+        .block(pane("Tags", app.focus == Focus::Tags))
+        .highlight_style(highlight());
+    f.render_stateful_widget(list, area, &mut app.tag_state);
+}
+
+pub fn difficulty_span(difficulty: Option<Difficulty>) -> Span<'static> {
+    match difficulty {
+        Some(Difficulty::Easy) => "Easy".green(),
+        Some(Difficulty::Medium) => "Medium".yellow(),
+        Some(Difficulty::Hard) => "Hard".red(),
+        None => "-".dark_gray(),
+    }
+}
+
+pub fn status_span(status: Status) -> Span<'static> {
+    match status {
+        Status::Finished => "Finished".green(),
+        Status::Hidden => "Hidden".dark_gray(),
+        Status::Attempted => "Attempted".into(),
+    }
+}
+
+fn draw_problems(f: &mut Frame, app: &mut App, area: Rect) {
+    let rows = app
+        .visible()
+        .into_iter()
+        .map(|prob| {
+            Row::new(vec![
+                Line::from(prob.number.to_string()),
+                Line::from(prob.title.clone()),
+                Line::from(difficulty_span(prob.meta.difficulty)),
+                Line::from(status_span(prob.meta.status)),
+            ])
+        })
+        .collect::<Vec<_>>();
+    let title = match app.tag_filter() {
+        Some(tag) => format!("Problems ({tag})"),
+        None => "Problems".to_owned(),
+    };
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(6),
+            Constraint::Min(10),
+            Constraint::Length(8),
+            Constraint::Length(10),
+        ],
+    )
+    .header(Row::new(vec!["#", "Title", "Difficulty", "Status"]).bold())
+    .block(pane(&title, app.focus == Focus::Problems))
+    .highlight_style(highlight());
+    f.render_stateful_widget(table, area, &mut app.table_state);
+}
+
+/// The metadata lines shown above the statement in the detail pane.
+pub fn metadata_lines(prob: &ProblemInfo) -> Vec<Line<'static>> {
+    let date = |a: Option<chrono::DateTime<chrono::Utc>>| {
+        a.map(|a| a.format("%Y-%m-%d").to_string())
+            .unwrap_or("-".to_owned())
+    };
+    vec![
+        Line::from(format!("{}. {}", prob.number, prob.title)).bold(),
+        Line::from(vec![
+            "Difficulty: ".into(),
+            difficulty_span(prob.meta.difficulty),
+            "  Status: ".into(),
+            status_span(prob.meta.status),
+        ]),
+        Line::from(format!(
+            "Created: {}  Finished: {}",
+            date(prob.meta.created),
+            date(prob.meta.finished)
+        )),
+        Line::from(format!(
+            "Tags: {}",
+            prob.tags.iter().cloned().collect::<Vec<_>>().join(", ")
+        )),
+        Line::from(prob.link.clone()).dark_gray(),
+    ]
+}
+
+fn draw_detail(f: &mut Frame, app: &mut App, area: Rect) {
+    let [meta, body] = Layout::vertical([Constraint::Length(7), Constraint::Min(3)]).areas(area);
+    let [statement, runs] =
+        Layout::horizontal([Constraint::Percentage(60), Constraint::Percentage(40)]).areas(body);
+    draw_runs(f, app, runs);
+
+    let Some(prob) = app.selected() else {
+        f.render_widget(
+            Paragraph::new("No problem selected").block(pane("Details", false)),
+            meta,
+        );
+        return;
+    };
+
+    let number = prob.number;
+    f.render_widget(
+        Paragraph::new(metadata_lines(prob)).block(pane("Details", false)),
+        meta,
+    );
+
+    let statement_text = Paragraph::new(app.statement(number).to_vec())
+        .block(pane("Statement", false))
+        .wrap(Wrap { trim: false })
+        .scroll((app.detail_scroll, 0));
+    f.render_widget(statement_text, statement);
+}
+
+fn draw_runs(f: &mut Frame, app: &mut App, area: Rect) {
+    let lines = if app.runs.history.is_empty() {
+        vec![
+            Line::from("r tests | x examples | s submit, or save a file to run the tests")
+                .dark_gray(),
+        ]
+    } else {
+        app.runs.history.iter().flat_map(run_lines).collect()
+    };
+    // the latest output sits at the bottom, scrolling moves up through older runs
+    let height = area.height.saturating_sub(2) as usize;
+    let bottom = lines.len().saturating_sub(height);
+    app.runs_scroll = app.runs_scroll.min(bottom as u16);
+    let top = bottom as u16 - app.runs_scroll;
+    let runs = Paragraph::new(lines)
+        .block(pane("Runs", app.focus == Focus::Runs))
+        .scroll((top, 0));
+    f.render_widget(runs, area);
+}
+
+/// The lines of a run in the runs pane: a header with its state, then the details of a failure.
+pub fn run_lines(run: &Run) -> Vec<Line<'static>> {
+    let state = run.state.compact();
+    let state = match &run.state {
+        RunState::Stage(_) => state.yellow(),
+        RunState::Tests(summary) if summary.success() => state.green(),
+        RunState::Judged(judgement) if judgement.passed => state.green(),
+        _ => state.red(),
+    };
+    let mut lines = vec![Line::from(vec![
+        run.started.format("%H:%M:%S ").to_string().dark_gray(),
+        format!("#{} {}: ", run.num, run.kind).bold(),
+        state,
+    ])];
+    let indent = |a: &str| Line::from(format!("  {a}"));
+    match &run.state {
+        RunState::Tests(summary) if !summary.success() => {
+            if let Some(error) = &summary.build_error {
+                lines.push(indent(error).red());
+            }
+            let mismatches = assertion_mismatches(&summary.output);
+            for a in &mismatches {
+                lines.extend(mismatch_lines(a));
+            }
+            // failures without an assert_eq! show their panic message instead
+            let described = mismatches.iter().map(|a| &a.label).collect::<Vec<_>>();
+            for failure in summary.failures.iter().filter(|a| !described.contains(a)) {
+                lines.push(indent(failure).bold());
+                lines.extend(panic_message(&summary.output, failure).map(|a| indent(&a).red()));
+            }
+        }
+        RunState::Judged(judgement) if !judgement.passed => {
+            if let Some(error) = &judgement.error {
+                lines.extend(error.lines().map(|a| indent(a).red()));
+            }
+            for a in judgement_mismatches(judgement) {
+                lines.extend(mismatch_lines(&a));
+            }
+        }
+        _ => {}
+    }
+    lines
+}
+
+/// The label of a mismatch then the expected and actual values, with the first difference
+/// highlighted.
+pub fn mismatch_lines(mismatch: &Mismatch) -> Vec<Line<'static>> {
+    let at = first_difference(&mismatch.actual, &mismatch.expected);
+    let diff = |sign: &str, value: &str, color: Color| {
+        let at = at.min(value.len());
+        Line::from(vec![
+            Span::styled(format!("  {sign} "), Style::default().fg(color)),
+            Span::styled(value[..at].to_owned(), Style::default().fg(color)),
+            Span::styled(
+                value[at..].to_owned(),
+                Style::default().fg(color).add_modifier(Modifier::REVERSED),
+            ),
+        ])
+    };
+    vec![
+        Line::from(format!("  {}", mismatch.label)).bold(),
+        diff("-", &mismatch.expected, Color::Green),
+        diff("+", &mismatch.actual, Color::Red),
+    ]
+}
+
+/// The message a test panicked with, e.g. from `assert!` or an index out of bounds.
+fn panic_message(output: &str, test: &str) -> Option<String> {
+    let start = format!("thread '{test}'");
+    let mut lines = output
+        .lines()
+        .skip_while(|a| !(a.starts_with(&start) && a.contains(" panicked at ")));
+    let location = lines.next()?.split_once(" panicked at ")?.1.trim();
+    // older versions of Rust quote the message before the location, newer ones put it on the
+    // following line
+    match location
+        .strip_prefix('\'')
+        .and_then(|a| a.rsplit_once("', "))
+    {
+        Some((message, _)) => Some(message.to_owned()),
+        None => lines.next().map(str::to_owned),
+    }
+}